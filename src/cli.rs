@@ -0,0 +1,74 @@
+use crate::zip_manager::conflict::Policy;
+
+pub const USAGE: &str = "Usage:
+\t{} [--overwrite | --skip | --rename | --keep-newer] {7zip file}
+\t{} --extract <dir> [--overwrite | --skip | --rename | --keep-newer] {7zip file} [members...]
+\t{} cache clear";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub archive: String,
+    // Extracts without opening the browser
+    pub extract: Option<String>,
+    pub members: Vec<String>,
+    pub policy: Option<Policy>,
+    // Empties the cache of opened files and exits
+    pub clear_cache: bool,
+}
+
+pub fn usage(program: &str) -> String {
+    USAGE.replace("{}", program)
+}
+
+// The arguments without the program name
+pub fn parse(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    if args.first().is_some_and(|arg| arg == "cache") {
+        return match &args[1..] {
+            [command] if command == "clear" => Ok(Args { clear_cache: true, ..parsed }),
+            _ => Err(String::from("The only cache command is 'cache clear'")),
+        };
+    }
+
+    let mut positional = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let policy = match arg.as_str() {
+            "--overwrite" => Policy::Overwrite,
+            "--skip" => Policy::Skip,
+            "--rename" => Policy::Rename,
+            "--keep-newer" => Policy::KeepNewer,
+            "-x" | "--extract" => {
+                let dir = args.next().ok_or_else(|| format!("'{}' needs a folder", arg))?;
+                parsed.extract = Some(dir.clone());
+                continue;
+            },
+            "--" => {
+                positional.extend(args.by_ref().cloned());
+                continue;
+            },
+            _ if arg.starts_with("--extract=") => {
+                parsed.extract = Some(String::from(&arg["--extract=".len()..]));
+                continue;
+            },
+            _ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("Unknown option '{}'", arg)),
+            _ => {
+                positional.push(arg.clone());
+                continue;
+            },
+        };
+
+        if parsed.policy.is_some_and(|chosen| chosen != policy) {
+            return Err(String::from("Only one of --overwrite, --skip, --rename and --keep-newer can be given"));
+        }
+        parsed.policy = Some(policy);
+    }
+
+    let mut positional = positional.into_iter();
+    parsed.archive = positional.next().ok_or_else(|| String::from("No compressed file given"))?;
+    parsed.members = positional.collect();
+    if parsed.extract.is_none() && !parsed.members.is_empty() {
+        return Err(String::from("Members can only be given with --extract"));
+    }
+    Ok(parsed)
+}
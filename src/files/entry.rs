@@ -1,4 +1,5 @@
 use crate::files::folder::Folder;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub enum Entry {
@@ -11,3 +12,57 @@ pub enum EntryType {
     File,
     Folder
 }
+
+#[derive(Clone, Debug, Default)]
+pub struct EntryInfo {
+    pub size: u64,
+    pub compressed: Option<u64>,
+    pub date: String,
+    pub folder: bool,
+}
+
+impl EntryInfo {
+    // Percentage of the original size, solid archives only know it for whole blocks
+    pub fn ratio(&self) -> Option<u64> {
+        match self.compressed {
+            Some(compressed) if self.size > 0 => Some(compressed * 100 / self.size),
+            _ => None,
+        }
+    }
+}
+
+// Every folder above the entry adds up its size and keeps the date of the newest entry
+pub fn add_to_folders(folders: &mut HashMap<String, EntryInfo>, name: &str, info: &EntryInfo) {
+    for (end, _) in name.match_indices('/') {
+        let folder = folders.entry(String::from(&name[..end]))
+            .or_insert_with(|| EntryInfo { folder: true, ..EntryInfo::default() });
+        folder.size += info.size;
+        if info.date > folder.date {
+            folder.date = info.date.clone();
+        }
+    }
+}
+
+pub fn folder_totals(info: &HashMap<String, EntryInfo>) -> HashMap<String, EntryInfo> {
+    let mut folders = HashMap::new();
+    for (name, entry) in info {
+        add_to_folders(&mut folders, name, entry);
+    }
+    folders
+}
+
+pub fn human_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, units[0])
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
@@ -14,6 +14,13 @@ impl Folder {
         }
     }
 
+    pub fn file_count(&self) -> usize {
+        self.content.iter().map(|entry| match entry {
+            Entry::File(_) => 1,
+            Entry::Folder(folder) => folder.file_count(),
+        }).sum()
+    }
+
     pub fn add_file(&mut self, file_name: &str) {
         self.content.push(Entry::File(file_name.to_string()));
     }
@@ -95,8 +102,7 @@ impl Folder {
     pub fn strace(&self, indent: usize) {
         let indent_char = " ".repeat(indent);
         println!("{}Folder: {}", " ".repeat(indent - 1) + "└┬", self.name);
-        let mut i = 0;
-        for entry in &self.content {
+        for (i, entry) in self.content.iter().enumerate() {
             match entry {
                 Entry::File(file_name) => {
                     if i + 1 < self.content.len() {
@@ -107,7 +113,6 @@ impl Folder {
                 },
                 Entry::Folder(folder) => folder.strace(indent + 1),
             }
-            i += 1;
         }
     }
 
@@ -0,0 +1,106 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    io,
+    os::unix::fs::DirBuilderExt,
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Workspaces still to be removed, so a panic or a signal does not leave them behind
+static PENDING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+// The folder where the opened files are extracted, only created once something needs it
+#[derive(Debug, Default)]
+pub struct Workspace {
+    root: Option<PathBuf>,
+    // Left on disk after exit
+    pub keep: bool,
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if let Err(err) = self.remove() {
+            eprintln!("{}", err);
+        }
+    }
+}
+
+impl Workspace {
+    pub fn new(keep: bool) -> Self {
+        Self { root: None, keep }
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    pub fn create(&mut self) -> io::Result<&Path> {
+        if self.root.is_none() {
+            let root = create_unique(&env::temp_dir())?;
+            if !self.keep {
+                PENDING.lock().unwrap().push(root.clone());
+            }
+            self.root = Some(root);
+        }
+        Ok(self.root.as_deref().unwrap())
+    }
+
+    // Every archive gets its own folder, named after it so it can be found when the files are kept
+    pub fn archive_dir(&mut self, archive: &str) -> io::Result<PathBuf> {
+        let archive = fs::canonicalize(archive).unwrap_or_else(|_| PathBuf::from(archive));
+        let mut hasher = DefaultHasher::new();
+        archive.hash(&mut hasher);
+        let name = archive.file_name().unwrap_or_default().to_string_lossy();
+        let dir = self.create()?.join(format!("{}.{:08x}", name, hasher.finish() as u32));
+        private_dir(&dir)?;
+        Ok(dir)
+    }
+
+    // Kept workspaces are only forgotten, the error says what could not be removed
+    pub fn remove(&mut self) -> Result<(), String> {
+        let Some(root) = self.root.take() else {
+            return Ok(());
+        };
+        if self.keep {
+            return Ok(());
+        }
+
+        PENDING.lock().unwrap().retain(|pending| *pending != root);
+        match fs::remove_dir_all(&root) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Cannot remove the temporary files in '{}': {}", root.display(), err)),
+        }
+    }
+}
+
+// Only the user can read what is extracted
+pub fn private_dir(dir: &Path) -> io::Result<()> {
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+}
+
+fn create_unique(parent: &Path) -> io::Result<PathBuf> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.subsec_nanos());
+    for attempt in 0..100u32 {
+        let dir = parent.join(format!("content-7z.{}.{:08x}", process::id(), nanos.wrapping_add(attempt.wrapping_mul(7919))));
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "no free name for the temporary folder"))
+}
+
+// Called when the program ends without dropping the workspace
+pub fn remove_pending() {
+    // A panic while the lock is held must not stop the cleanup
+    let mut pending = PENDING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for root in pending.drain(..) {
+        let _ = fs::remove_dir_all(root);
+    }
+}
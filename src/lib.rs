@@ -1,17 +1,38 @@
 pub mod files {
     pub mod entry;
     pub mod folder;
+    pub mod workspace;
 }
 
 pub mod window {
     pub mod cursor;
+    #[allow(clippy::module_inception)]
     pub mod window;
     pub mod scheme;
     pub mod handler;
+    pub mod preview;
+    pub mod viewer;
+    pub mod opener;
+    pub mod input;
+    pub mod select;
+    pub mod keymap;
+    pub mod palette;
+    pub mod breadcrumbs;
+    pub mod text;
+    pub mod screen;
+    pub mod render;
+    pub mod term;
 }
 
 pub mod zip_manager {
     pub mod manager;
+    pub mod reader;
+    pub mod task;
+    pub mod listing;
+    pub mod conflict;
+    pub mod safety;
+    pub mod cache;
 }
 
 pub mod config;
+pub mod cli;
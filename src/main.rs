@@ -1,245 +1,755 @@
 use crossterm::{
-    self, terminal::{self, Clear, ClearType}, cursor::MoveTo,
+    self, terminal, cursor::MoveTo,
     QueueableCommand,
-    event::{self, Event, KeyCode}
+    event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind}
 };
 
 use std::{
-    time::Duration, io::{stdout, Write}, thread,
-    process::{exit, Command, Stdio}, env,
+    time::Duration, io::stdout, fs,
+    process::{exit, Command}, env,
 };
 
 use content_7z::{
-    files::entry::Entry,
+    files::{entry::{human_size, Entry, EntryInfo}, folder::Folder, workspace},
     window::{
         window::Window,
-        scheme::NOCOLOR,
         handler::{Handler, HandleSituatonType, NormalHandler},
+        preview::Preview,
+        viewer::{Viewer, ViewerSource},
+        opener,
+        input::Input,
+        select::Select,
+        keymap::Action,
+        palette::Command as PaletteCommand,
+        breadcrumbs,
+        scheme::Scheme,
+        term,
+        render::{
+            close_dialog, print_header, print_help, print_input, print_menu, print_palette,
+            print_preview, print_row, print_select, print_status, print_task, print_viewer, show_dialog,
+            show_dialog_raw,
+        },
     },
-    config
+    config, cli,
 };
 
-use content_7z::zip_manager::manager::ZipManager;
-use std::path::PathBuf;
-
-fn print_header(win: &Window) {
-    let fill_all_block = "─".repeat(usize::from(win.width) - 2);
-    let stdout = unsafe { &mut (*win.writer) };
-    let path = win.get_path() + win.plain_current().as_str();
-
-    stdout.queue(MoveTo(0, 0)).unwrap();
-    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write(("┌".to_string() + fill_all_block.as_str() + "┐").as_bytes()).unwrap();
-
-    stdout.queue(MoveTo(0, 1)).unwrap();
-    stdout.queue(Clear(ClearType::CurrentLine)).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write("│".as_bytes()).unwrap();
-    if path.len() > (win.width - 2).into() {
-        stdout.write(win.scheme.text_color.repr.as_bytes()).unwrap();
-        stdout.write("...".as_bytes()).unwrap();
-        stdout.write(&path.as_bytes()[path.len() - usize::from(win.width - 5)..path.len()]).unwrap();
-    } else {
-        stdout.write(win.scheme.text_color.repr.as_bytes()).unwrap();
-        stdout.write(path.as_bytes()).unwrap();
-    }
-
-    stdout.queue(MoveTo(win.width - 1, 1)).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write("│".as_bytes()).unwrap();
-
-    stdout.queue(MoveTo(0, 2)).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write(("└".to_string() + fill_all_block.as_str() + "┘").as_bytes()).unwrap();
-    stdout.write(NOCOLOR).unwrap();
-}
-
-fn print_menu(win: &Window) {
-    let fill_all_block = "─".repeat(usize::from(win.width) - 2);
-    let stdout = unsafe { &mut (*win.writer) };
-
-    stdout.queue(MoveTo(0, 3)).unwrap();
-    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write(("┌".to_string() + fill_all_block.as_str() + "┐").as_bytes()).unwrap();
-
-    for i in 4..win.height {
-        stdout.queue(MoveTo(0, i)).unwrap();
-        stdout.queue(terminal::Clear(ClearType::CurrentLine)).unwrap();
-        stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-        stdout.write("│".as_bytes()).unwrap();
-
-        if win.get_current().content.len() > (i - 4 + win.scroll_y).into() {
-            let entry = &win.get_current().content[usize::from(i - 4 + win.scroll_y)];
-            let _ = match entry {
-                Entry::File(file_name) => {
-                    stdout.write(NOCOLOR).unwrap();
-                    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.file_bullet_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.file_bullet.as_bytes()).unwrap();
-                    stdout.write(NOCOLOR).unwrap();
-                    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.text_color.repr.as_bytes()).unwrap();
-                    stdout.write(file_name.as_bytes()).unwrap()
-                },
-                Entry::Folder(folder) => {
-                    stdout.write(NOCOLOR).unwrap();
-                    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.folder_bullet_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.folder_bullet.as_bytes()).unwrap();
-                    stdout.write(NOCOLOR).unwrap();
-                    stdout.write(win.scheme.background_color.repr.as_bytes()).unwrap();
-                    stdout.write(win.scheme.text_color.repr.as_bytes()).unwrap();
-                    stdout.write(folder.name.as_bytes()).unwrap()
-                },
-            };
+use content_7z::zip_manager::{
+    cache::{self, Cache},
+    conflict::{self, Choice, Conflict, Policy, Resolution},
+    safety,
+    listing::Listing, manager::ZipManager, reader::{MemberReader, PipedCommand}, task::{self, Progress, Task},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use which::which;
+
+type OpenJob = fn(&mut Window, String, PathBuf);
+
+#[derive(Clone)]
+enum OpenChoice {
+    Command(String),
+    Editor,
+    Viewer,
+}
+
+// An extraction with the files it would overwrite, asked about one by one
+#[derive(Clone)]
+struct Extraction {
+    dir: String,
+    // Path inside the archive, None for the whole archive
+    member: Option<String>,
+    shown: String,
+    entry: Entry,
+    // The files that can be extracted, and the ones left out for being unsafe
+    files: Vec<String>,
+    blocked: Vec<String>,
+    conflicts: Vec<Conflict>,
+    next: usize,
+    policy: Policy,
+    resolution: Resolution,
+}
+
+// Lines of a 7z report shown when something fails
+const ERROR_LINES: usize = 5;
+
+const CONFLICT_CHOICES: [(&str, Policy); 4] = [
+    ("Overwrite", Policy::Overwrite),
+    ("Skip", Policy::Skip),
+    ("Rename", Policy::Rename),
+    ("Keep newer", Policy::KeepNewer),
+];
+
+fn open_viewer(win: &mut Window, file_name: String) {
+    match Viewer::member(&win.get_path(), &file_name[1..], win.width, win.height) {
+        Ok(viewer) => {
+            win.viewer = Some(viewer);
+            print_viewer(win);
+        },
+        Err(err) => show_err_dialog(win, err.as_str(), false),
+    }
+}
+
+fn pipe_file(win: &mut Window, file_name: String, command_line: String) {
+    let reader = match MemberReader::open(&win.get_path(), &file_name[1..]) {
+        Ok(reader) => reader,
+        Err(err) => {
+            show_err_dialog(win, err.as_str(), false);
+            return;
+        },
+    };
+
+    match reader.pipe_into(opener::shell(&command_line)) {
+        Ok(piped) => {
+            let title = format!("{} | {}", &file_name[1..], command_line);
+            win.viewer = Some(Viewer::pipe(&title, piped, win.width, win.height));
+            print_viewer(win);
+        },
+        Err(err) => show_err_dialog(win, err.as_str(), false),
+    }
+}
+
+// What a piped command prints is shown as it arrives
+fn update_viewer(win: &mut Window) {
+    let Some(viewer) = win.viewer.as_mut() else {
+        return;
+    };
+    if !viewer.update() {
+        return;
+    }
+
+    if let ViewerSource::Pipe(PipedCommand { content, status: Some(status), .. }) = &viewer.source {
+        if content.is_empty() {
+            let message = format!("'{}' finished ({}) without output", viewer.title, status);
+            close_viewer(win);
+            win.set_message(message);
+            print_status(win);
+            return;
         }
+    }
+    print_viewer(win);
+}
+
+fn close_viewer(win: &mut Window) {
+    win.viewer = None;
+    close_dialog(win);
+}
+
+fn viewer_key(win: &mut Window, key: KeyCode) {
+    let Some(viewer) = win.viewer.as_mut() else {
+        return;
+    };
 
-        stdout.queue(MoveTo(win.width - 1, i)).unwrap();
-        stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-        stdout.write("│".as_bytes()).unwrap();
+    if let Some(prompt) = viewer.prompt.as_mut() {
+        match key {
+            KeyCode::Char(ch) => prompt.push(ch),
+            KeyCode::Backspace => {
+                prompt.pop();
+            },
+            KeyCode::Enter => {
+                viewer.search = viewer.prompt.take().unwrap();
+                viewer.find(true, true);
+            },
+            KeyCode::Esc => viewer.prompt = None,
+            _ => {},
+        }
+        print_viewer(win);
+        return;
     }
 
-    stdout.queue(MoveTo(0, win.height - 1)).unwrap();
-    stdout.write(win.scheme.border_color.repr.as_bytes()).unwrap();
-    stdout.write(("└".to_string() + fill_all_block.as_str() + "┘").as_bytes()).unwrap();
-    stdout.write(NOCOLOR).unwrap();
+    // Going to the end or searching stops at any key, Esc and q only stop it
+    if viewer.stop() && matches!(key, KeyCode::Esc | KeyCode::Char('q')) {
+        print_viewer(win);
+        return;
+    }
+    let page = viewer.page_size();
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            close_viewer(win);
+            return;
+        },
+        KeyCode::Up | KeyCode::Char('k') => viewer.scroll_up(1),
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Enter => viewer.scroll_down(1),
+        KeyCode::PageUp | KeyCode::Char('b') => viewer.scroll_up(page),
+        KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => viewer.scroll_down(page),
+        KeyCode::Home | KeyCode::Char('g') => viewer.scroll_top(),
+        KeyCode::End | KeyCode::Char('G') => viewer.scroll_bottom(),
+        KeyCode::Tab => viewer.toggle_mode(),
+        KeyCode::Char('/') => viewer.prompt = Some(String::new()),
+        KeyCode::Char('n') => viewer.find(true, false),
+        KeyCode::Char('N') => viewer.find(false, false),
+        _ => {},
+    }
+    print_viewer(win);
 }
 
-fn show_dialog_raw(win: &mut Window, text: String, helper: Option<&str>) {
-    let stdout = unsafe { &mut (*win.writer) };
+fn update_preview(win: &mut Window) {
+    if !win.preview.enabled || win.on_dialog || win.viewer.is_some() {
+        return;
+    }
 
-    let lines_iter = text.split("\n");
-    let mut lines = vec![];
-    let mut max_length = 0;
+    let member = match win.get_selected() {
+        Some(Entry::File(file_name)) | Some(Entry::Folder(Folder { name: file_name, .. })) => win.plain_member() + "/" + file_name,
+        None => String::new(),
+    };
 
-    'scanner:
-    for line in lines_iter {
-        if line.len() > usize::from(win.width - 8) {
-            max_length = win.width - 8;
-            let mut index: usize = 0;
-            loop {
-                if line.len() < usize::from(max_length) + index {
-                    lines.push(&line[index..]);
-                    continue 'scanner;
-                }
+    win.preview.forget_unless(&member);
+    if win.preview.update() {
+        print_preview(win);
+        win.cursor.need_update = true;
+    }
+    if !win.preview.want(&member) {
+        return;
+    }
+
+    match win.get_selected() {
+        Some(Entry::File(_)) => win.preview.load(&win.get_path(), &member, win.scheme.preview_size),
+        Some(Entry::Folder(folder)) => win.preview.show(Preview::folder(&member, folder)),
+        None => win.preview.clear(),
+    }
+
+    print_preview(win);
+    win.cursor.need_update = true;
+}
+
+fn show_multiple_choice_dialog<T: Handler + 'static>(win: &mut Window, quest: String, handler: T) {
+    let helper = win.scheme.multi_choice_dialog_helper.clone();
+    show_dialog_raw(win, quest, Some(helper.as_str()));
+
+    win.handler = Some(Box::new(handler));
+}
+
+fn show_input_dialog<T: Handler + 'static>(win: &mut Window, quest: &str, purpose: &'static str, handler: T) {
+    win.input = Some(Input::with_history(quest, purpose, win.history_for(purpose)));
+    win.handler = Some(Box::new(handler));
+    print_input(win);
+}
+
+fn input_key(win: &mut Window, key: &KeyEvent) {
+    let Some(input) = win.input.as_mut() else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Enter => {
+            let input = win.input.take().unwrap();
+            win.remember_input(&input);
+            close_dialog(win);
+            win.run_job(HandleSituatonType::TEXT(input.value));
+            return;
+        },
+        KeyCode::Esc => {
+            win.input = None;
+            close_dialog(win);
+            win.run_job(HandleSituatonType::DENIED);
+            return;
+        },
+        _ => if !input.key(key) {
+            return;
+        },
+    }
+
+    close_dialog(win);
+    print_input(win);
+}
+
+fn input_paste(win: &mut Window, pasted: &str) {
+    if let Some(input) = win.input.as_mut() {
+        input.insert_str(pasted);
+        close_dialog(win);
+        print_input(win);
+    }
+}
 
-                lines.push(&line[index..index + usize::from(max_length)]);
-                index += usize::from(max_length);
+fn show_select_dialog<T: Handler + 'static>(win: &mut Window, select: Select, handler: T) {
+    win.select = Some(select);
+    win.handler = Some(Box::new(handler));
+    print_select(win);
+}
+
+fn select_key(win: &mut Window, key: &KeyEvent) {
+    let page = usize::from(win.height).saturating_sub(12);
+    let Some(select) = win.select.as_mut() else {
+        return;
+    };
+
+    let chosen = match key.code {
+        KeyCode::Enter => select.chosen(),
+        KeyCode::Char(ch) if select.filter.is_none() && ch.is_ascii_digit() => {
+            match ch.to_digit(10) {
+                Some(digit) if digit > 0 && (digit as usize) <= select.items.len() => Some(digit as usize - 1),
+                _ => return,
+            }
+        },
+        KeyCode::Esc => {
+            win.select = None;
+            close_dialog(win);
+            win.run_job(HandleSituatonType::DENIED);
+            return;
+        },
+        _ => {
+            if select.key(key, page) {
+                close_dialog(win);
+                print_select(win);
             }
-        } 
+            return;
+        },
+    };
 
-        if line.len() > usize::from(max_length) {
-            max_length = line.len() as u16;
-        }
-        lines.push(line);
+    if let Some(index) = chosen {
+        win.select = None;
+        close_dialog(win);
+        win.run_job(HandleSituatonType::CHOICE(index));
     }
+}
 
-    let mut helper_label = vec![];
+fn help_key(win: &mut Window, key: KeyCode) {
+    let Some(scroll) = win.help else {
+        return;
+    };
 
-    if let Some(label) = helper {
-        'scanner:
-        for line in label.split('\n') {
-            if line.len() > usize::from(win.width - 8) {
-                max_length = win.width - 8;
-                let mut index: usize = 0;
-                loop {
-                    if line.len() < usize::from(max_length) + index {
-                        helper_label.push(&line[index..]);
-                        continue 'scanner;
-                    }
+    let page = usize::from(win.height).saturating_sub(10).max(1);
+    win.help = match key {
+        KeyCode::Up | KeyCode::Char('k') => Some(scroll.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => Some(scroll + 1),
+        KeyCode::PageUp => Some(scroll.saturating_sub(page)),
+        KeyCode::PageDown | KeyCode::Char(' ') => Some(scroll + page),
+        KeyCode::Home => Some(0),
+        KeyCode::End => Some(usize::MAX),
+        _ => None,
+    };
 
-                    helper_label.push(&line[index..index + usize::from(max_length)]);
-                    index += usize::from(max_length);
-                }
-            } 
+    match win.help {
+        Some(_) => print_help(win),
+        None => close_dialog(win),
+    }
+}
 
-            if line.len() > usize::from(max_length) {
-                max_length = line.len() as u16;
+// Returns true when the command asked to quit
+fn palette_key(win: &mut Window, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(ch) => win.palette.push(ch),
+        KeyCode::Backspace if win.palette.value.is_empty() => {
+            win.palette.close();
+            close_dialog(win);
+            return false;
+        },
+        KeyCode::Backspace => win.palette.pop(),
+        KeyCode::Tab => win.palette.select_next(true),
+        KeyCode::BackTab => win.palette.select_next(false),
+        KeyCode::Up => win.palette.history_back(),
+        KeyCode::Down => win.palette.history_forward(),
+        KeyCode::Esc => {
+            win.palette.close();
+            close_dialog(win);
+            return false;
+        },
+        KeyCode::Enter => {
+            // Nothing to run while no command matches
+            let Some(command) = win.palette.chosen() else {
+                return false;
+            };
+            let args = String::from(win.palette.split().1);
+            let line = win.palette.value.clone();
+            win.palette.remember(&line);
+            win.palette.close();
+            close_dialog(win);
+            return run_command(win, command, &args);
+        },
+        _ => return false,
+    }
+
+    print_palette(win);
+    false
+}
+
+fn run_command(win: &mut Window, command: PaletteCommand, args: &str) -> bool {
+    match command {
+        PaletteCommand::Action(Action::Quit) => return true,
+        PaletteCommand::Action(action) => {
+            run_action(win, action);
+        },
+        PaletteCommand::Extract => extract_entry(win, if args.is_empty() { "." } else { args }),
+        PaletteCommand::Test => test_archive(win),
+        PaletteCommand::Cd => {
+            if !win.go_to(args) {
+                show_err_dialog(win, format!("No folder '{}' in the archive.", args).as_str(), false);
             }
-            helper_label.push(line);
-        }
+        },
     }
+    false
+}
 
-    let helper_label_increment = if helper_label.len() < 2 {
-        0
-    } else {
-        helper_label.len() - 1
+// Paths an extraction into the folder would create, what already exists is left alone
+fn new_paths(path: PathBuf, entry: &Entry) -> Vec<PathBuf> {
+    match entry {
+        _ if !path.exists() => vec![path],
+        Entry::Folder(folder) => folder.content.iter()
+            .filter_map(|entry| {
+                let name = Window::entry_name(entry);
+                let inside = safety::safe_join(&path, name).filter(|_| !matches!(name, "" | "." | ".."))?;
+                Some(new_paths(inside, entry))
+            })
+            .flatten()
+            .collect(),
+        Entry::File(_) => vec![],
+    }
+}
+
+type Members = Vec<(String, EntryInfo)>;
+
+// Files of the entry with their paths in the archive, None is the root of the archive
+fn entry_files(win: &Window, member: Option<&str>, entry: &Entry) -> Members {
+    match entry {
+        Entry::File(_) => {
+            let member = member.unwrap_or_default();
+            vec![(String::from(member), win.archive_info().get(member).cloned().unwrap_or_default())]
+        },
+        Entry::Folder(folder) => folder.content.iter()
+            .flat_map(|entry| {
+                let name = Window::entry_name(entry);
+                let path = match member {
+                    Some(member) => format!("{}/{}", member, name),
+                    None => String::from(name),
+                };
+                entry_files(win, Some(&path), entry)
+            })
+            .collect(),
+    }
+}
+
+// The files of the entry that can be extracted, and the members under it that cannot
+fn split_unsafe(win: &Window, member: Option<&str>, entry: &Entry) -> (Members, Members) {
+    let (files, mut blocked): (Vec<_>, Vec<_>) = entry_files(win, member, entry)
+        .into_iter()
+        .partition(|(name, _)| safety::hazard(win.archive_info(), name).is_none());
+    // Folders count too, 7z would create them wherever they say
+    let inside = |name: &str| member.map_or(true, |member| {
+        name == member || name.strip_prefix(member).is_some_and(|rest| rest.starts_with('/'))
+    });
+    blocked.extend(win.archive_info().iter()
+        .filter(|(name, info)| info.folder && inside(name) && safety::path_hazard(name).is_some())
+        .map(|(name, info)| (name.clone(), info.clone())));
+    (files, blocked)
+}
+
+// Extracts the selected entry keeping its path, or the current folder when nothing is selected
+fn extract_entry(win: &mut Window, dir: &str) {
+    let at_root = win.current.len() == win.nested.last().map_or(0, |nested| nested.depth) + 1;
+    let (member, entry) = match win.get_selected() {
+        Some(entry) => (Some(win.member_path(Window::entry_name(entry))), entry.clone()),
+        None if at_root => (None, Entry::Folder(win.get_current().clone())),
+        None => (Some(String::from(&win.plain_member()[1..])), Entry::Folder(win.get_current().clone())),
     };
 
-    let x: u16 = win.width / 2 - (max_length + 2) / 2;
-    let y: u16 = win.height / 2 - (lines.len() + helper_label_increment + 2) as u16 / 2;
+    let (files, blocked) = split_unsafe(win, member.as_deref(), &entry);
+    if matches!(entry, Entry::File(_)) && !blocked.is_empty() {
+        let hazard = win.hazard(&entry).map_or("", |hazard| hazard.describe());
+        show_err_dialog(win, format!("'{}' is not extracted, it is unsafe: {}.", blocked[0].0, hazard).as_str(), false);
+        return;
+    }
 
-    let fill_all_block = "─".repeat(usize::from(max_length));
+    let extraction = Extraction {
+        dir: String::from(dir),
+        shown: member.clone().unwrap_or_else(|| win.get_path()),
+        conflicts: conflict::find(Path::new(dir), files.clone()),
+        files: files.into_iter().map(|(name, _)| name).collect(),
+        blocked: blocked.into_iter().map(|(name, _)| name).collect(),
+        member,
+        entry,
+        next: 0,
+        policy: win.scheme.overwrite,
+        resolution: Resolution::default(),
+    };
+    resolve_conflicts(win, extraction);
+}
 
-    stdout.queue(MoveTo(x, y)).unwrap();
-    stdout.write("┌".as_bytes()).unwrap();
-    stdout.write(fill_all_block.as_bytes()).unwrap();
-    stdout.write("┐".as_bytes()).unwrap();
+fn conflict_select(conflict: &Conflict, remaining: usize, always: bool) -> Select {
+    let describe = |size: u64, date: &str| match date.is_empty() {
+        true => human_size(size),
+        false => format!("{} · {}", human_size(size), date),
+    };
+    let mut quest = format!(
+        "'{}' {}.\nIn the archive: {}\nOn disk:        {}",
+        conflict.member,
+        if always { "was extracted before" } else { "already exists" },
+        describe(conflict.archived.size, &conflict.archived.date),
+        describe(conflict.size, &conflict.date),
+    );
+    if remaining > 0 {
+        quest += format!("\n{} more file{} already exist{}.", remaining, if remaining == 1 { "" } else { "s" }, if remaining == 1 { "s" } else { "" }).as_str();
+    }
 
-    for (index, line) in lines.iter().enumerate() {
-        stdout.queue(MoveTo(x, y + 1 + index as u16)).unwrap();
-        stdout.write("│".as_bytes()).unwrap();
-        stdout.write(line.as_bytes()).unwrap();
-        stdout.queue(MoveTo(x + max_length + 1, y + 1 + index as u16)).unwrap();
-        stdout.write("│".as_bytes()).unwrap();
+    let mut labels: Vec<String> = CONFLICT_CHOICES.iter().map(|(label, _)| String::from(*label)).collect();
+    if always {
+        labels.extend(CONFLICT_CHOICES.iter().map(|(label, _)| format!("Always {}", label.to_lowercase())));
+    } else if remaining > 0 {
+        labels.extend(CONFLICT_CHOICES.iter().map(|(label, _)| format!("{} all", label)));
     }
+    let labels = labels.into_iter().enumerate().map(|(index, label)| format!("{}. {}", index + 1, label)).collect();
+    Select::new(&quest, labels)
+}
 
-    stdout.queue(MoveTo(x, y + 1 + lines.len() as u16)).unwrap();
-    if helper_label.len() < 2 {
-        stdout.write("└".as_bytes()).unwrap();
-        stdout.write(fill_all_block.as_bytes()).unwrap();
-        stdout.write("┘".as_bytes()).unwrap();
+// The policy picked in the conflict dialog, and whether it is for the rest too
+fn conflict_choice(index: usize) -> (Policy, bool) {
+    (CONFLICT_CHOICES[index % CONFLICT_CHOICES.len()].1, index >= CONFLICT_CHOICES.len())
+}
 
-        if helper_label.len() == 1 {
-            stdout.queue(MoveTo(x + max_length / 2 - helper_label[0].len() as u16 / 2 + 1, y + 1 + lines.len() as u16)).unwrap();
-            stdout.write(helper_label[0].as_bytes()).unwrap();
+fn resolve_conflicts(win: &mut Window, mut extraction: Extraction) {
+    while let Some(conflict) = extraction.conflicts.get(extraction.next) {
+        match conflict.choice(extraction.policy) {
+            Some(choice) => {
+                extraction.resolution.add(conflict, choice);
+                extraction.next += 1;
+            },
+            None => {
+                let remaining = extraction.conflicts.len() - extraction.next - 1;
+                let select = conflict_select(conflict, remaining, false);
+                let job = NormalHandler::new(|win, situation, extraction| match situation {
+                    HandleSituatonType::CHOICE(index) => {
+                        let mut extraction = extraction.clone();
+                        let (policy, rest) = conflict_choice(index);
+                        if rest {
+                            extraction.policy = policy;
+                        }
+                        let conflict = &extraction.conflicts[extraction.next];
+                        let choice = conflict.choice(policy).unwrap();
+                        extraction.resolution.add(&conflict.clone(), choice);
+                        extraction.next += 1;
+                        resolve_conflicts(win, extraction);
+                    },
+                    HandleSituatonType::DENIED => {
+                        win.set_message(format!("Extraction of '{}' cancelled", extraction.shown));
+                        print_status(win);
+                    },
+                    _ => {},
+                }, extraction);
+                show_select_dialog(win, select, job);
+                return;
+            },
         }
-    } else {
-        stdout.write("├".as_bytes()).unwrap();
-        stdout.write(fill_all_block.as_bytes()).unwrap();
-        stdout.write("┤".as_bytes()).unwrap();
-
-        stdout.queue(MoveTo(x, y + helper_label.len() as u16 + lines.len() as u16)).unwrap();
-        stdout.write("└".as_bytes()).unwrap();
-        stdout.write(fill_all_block.as_bytes()).unwrap();
-        stdout.write("┘".as_bytes()).unwrap();
-
-        for (index, label) in helper_label.iter().enumerate() {
-            if index != 0 && index != helper_label.len() - 1 {
-                stdout.queue(MoveTo(x, y + 1 + lines.len() as u16 + index as u16)).unwrap();
-                stdout.write("│".as_bytes()).unwrap();
-            }
+    }
+    start_extraction(win, extraction);
+}
+
+fn start_extraction(win: &mut Window, extraction: Extraction) {
+    // Skipped and renamed files may leave nothing for the main run
+    let pending = extraction.files.iter().any(|file| !extraction.resolution.is_resolved(file));
+    if !pending && (!extraction.files.is_empty() || !extraction.blocked.is_empty()) {
+        start_renames(win, extraction);
+        return;
+    }
+
+    // With unsafe files inside, the safe ones are named one by one instead of the folder,
+    // and naming a member also takes what is under it, so the unsafe ones are left out by name
+    let dir = extraction.dir.as_str();
+    let members: Vec<String> = match &extraction.member {
+        _ if !extraction.blocked.is_empty() => extraction.files.clone(),
+        Some(member) => vec![member.clone()],
+        None => vec![],
+    };
+    let args = extraction.resolution.args(&win.get_path(), dir, &members, &extraction.blocked);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let task = Task::start(&format!("Extracting '{}'", extraction.shown), &args).map(|mut task| {
+        task.total_bytes = win.entry_size(&extraction.entry);
+        task.total_files = extraction.files.len();
+        task.set_partial(match Path::new(dir).exists() {
+            true => safety::safe_join(Path::new(dir), extraction.member.as_deref().unwrap_or_default())
+                .map_or(vec![], |path| new_paths(path, &extraction.entry)),
+            false => vec![PathBuf::from(dir)],
+        });
+        task
+    });
+
+    let job = NormalHandler::new(|win, situation, extraction| match situation {
+        HandleSituatonType::SUCESS(_) => start_renames(win, extraction.clone()),
+        _ => extraction_ended(win, situation, &extraction),
+    }, extraction);
+    start_task(win, task, job);
+}
+
+// The renamed files go in a run of their own, 7z gives them a free name
+fn start_renames(win: &mut Window, extraction: Extraction) {
+    let Some(args) = extraction.resolution.rename_args(&win.get_path(), &extraction.dir, &extraction.blocked) else {
+        extraction_ended(win, HandleSituatonType::SUCESS(true), &extraction);
+        return;
+    };
 
-            stdout.queue(MoveTo(x + max_length / 2 - label.len() as u16 / 2 + 1, y + 1 + lines.len() as u16 + index as u16)).unwrap();
-            stdout.write(label.as_bytes()).unwrap();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let task = Task::start(&format!("Extracting renamed copies of '{}'", extraction.shown), &args).map(|mut task| {
+        let renamed: Vec<&String> = extraction.resolution.rename.iter().map(|(member, _)| member).collect();
+        task.total_bytes = extraction.conflicts.iter()
+            .filter(|conflict| renamed.contains(&&conflict.member))
+            .map(|conflict| conflict.archived.size)
+            .sum();
+        task.total_files = renamed.len();
+        task.set_partial(extraction.resolution.rename.iter().map(|(_, path)| path.clone()).collect());
+        task
+    });
+
+    let job = NormalHandler::new(|win, situation, extraction| extraction_ended(win, situation, &extraction), extraction);
+    start_task(win, task, job);
+}
 
-            if index != 0 && index != helper_label.len() - 1 {
-                stdout.queue(MoveTo(x + max_length + 1, y + 1 + lines.len() as u16 + index as u16)).unwrap();
-                stdout.write("│".as_bytes()).unwrap();
+// Where the files of the extraction ended up, the skipped ones were not touched
+fn extracted_paths(extraction: &Extraction) -> Vec<PathBuf> {
+    let root = Path::new(&extraction.dir);
+    extraction.files.iter()
+        .filter(|file| !extraction.resolution.is_resolved(file))
+        .filter_map(|file| safety::safe_join(root, file))
+        .chain(extraction.resolution.rename.iter().map(|(_, path)| path.clone()))
+        .collect()
+}
+
+fn extraction_ended(win: &mut Window, situation: HandleSituatonType, extraction: &Extraction) {
+    match situation {
+        HandleSituatonType::SUCESS(_) => {
+            let mut summary = vec![extraction.resolution.summary()];
+            if !extraction.blocked.is_empty() {
+                summary.push(format!("{} unsafe left out", extraction.blocked.len()));
             }
-        }
+            let links = safety::settle_links(Path::new(&extraction.dir), &extracted_paths(extraction), win.scheme.links);
+            if links > 0 {
+                summary.push(format!("{} link{} removed", links, if links == 1 { "" } else { "s" }));
+            }
+            summary.retain(|part| !part.is_empty());
+
+            let message = match summary.join(", ") {
+                _ if matches!(extraction.entry, Entry::File(_)) && extraction.resolution.skip == extraction.files => {
+                    format!("'{}' already exists in '{}', skipped", extraction.shown, extraction.dir)
+                },
+                summary if summary.is_empty() => format!("Extracted '{}' into '{}'", extraction.shown, extraction.dir),
+                summary => format!("Extracted '{}' into '{}' ({})", extraction.shown, extraction.dir, summary),
+            };
+            win.set_message(message);
+            print_status(win);
+        },
+        HandleSituatonType::TEXT(err) => {
+            show_err_dialog(win, format!("Cannot extract '{}'.\n{}", extraction.shown, last_lines(&err)).as_str(), false);
+        },
+        HandleSituatonType::DENIED => {
+            win.set_message(format!("Extraction of '{}' cancelled", extraction.shown));
+            print_status(win);
+        },
+        _ => {},
     }
+}
 
-    win.on_dialog = true;
+fn test_archive(win: &mut Window) {
+    let path = win.get_path();
+    let task = Task::start(&format!("Testing '{}'", path), &["t", "--", path.as_str()]).map(|mut task| {
+        task.total_bytes = win.archive_info().values().map(|info| info.size).sum();
+        task.total_files = win.current[win.nested.last().map_or(0, |nested| nested.depth)].file_count();
+        task
+    });
+
+    let job = NormalHandler::new(|win, situation, path| match situation {
+        HandleSituatonType::SUCESS(_) => {
+            win.set_message(format!("'{}' is OK", path));
+            print_status(win);
+        },
+        HandleSituatonType::TEXT(report) => {
+            show_err_dialog(win, format!("'{}' has errors.\n{}", path, last_lines(&report)).as_str(), false);
+        },
+        _ => {},
+    }, path);
+    start_task(win, task, job);
+}
 
-    if helper_label.len() == 0 {
-        stdout.queue(MoveTo(win.width / 2, win.height / 2)).unwrap();
-    } else {
-        stdout.queue(MoveTo(x + max_length / 2 + 1, y + (lines.len() + helper_label.len() / 2) as u16 + 1)).unwrap();
+// Returns the error when the archive could not be listed at all
+fn update_loading(win: &mut Window) -> Option<String> {
+    let (entries, end) = win.loading.as_mut()?.update();
+    win.path_change = true;
+    win.add_listed(entries);
+
+    let count = win.loading.as_ref().map_or(0, |listing| listing.count);
+    match end? {
+        Ok(()) => {
+            win.loading = None;
+            None
+        },
+        Err(err) if count == 0 => Some(err),
+        Err(err) => {
+            win.loading = None;
+            show_err_dialog(win, format!("The listing ended with errors.\n{}", last_lines(&err)).as_str(), false);
+            None
+        },
     }
 }
 
-fn show_dialog(win: &mut Window, text: String) {
-    show_dialog_raw(win, text, None);
-} 
+fn start_task<T: Handler + 'static>(win: &mut Window, task: Result<Task, String>, handler: T) {
+    match task {
+        Ok(task) => {
+            win.task = Some(task);
+            win.handler = Some(Box::new(handler));
+            print_task(win);
+        },
+        Err(err) => show_err_dialog(win, &err, false),
+    }
+}
 
-fn show_multiple_choice_dialog<T: Handler + 'static>(win: &mut Window, quest: String, handler: T) {
-    let helper = win.scheme.multi_choice_dialog_helper.clone();
-    show_dialog_raw(win, quest, Some(helper.as_str()));
+// The handler gets SUCESS when the task ends well, TEXT with the errors when it fails and DENIED when it is cancelled
+fn update_task(win: &mut Window) {
+    let Some(task) = win.task.as_mut() else {
+        return;
+    };
 
-    win.handler = Some(Box::new(handler));
+    match task.update() {
+        None => print_task(win),
+        Some(result) => {
+            win.task = None;
+            close_dialog(win);
+            match result {
+                Ok(()) => win.run_job(HandleSituatonType::SUCESS(true)),
+                Err(err) => win.run_job(HandleSituatonType::TEXT(err)),
+            }
+        },
+    }
+}
+
+fn cancel_task(win: &mut Window) {
+    if let Some(mut task) = win.task.take() {
+        task.cancel();
+        close_dialog(win);
+        win.run_job(HandleSituatonType::DENIED);
+    }
+}
+
+fn breadcrumb_key(win: &mut Window, key: KeyCode) {
+    let Some(selected) = win.breadcrumb else {
+        return;
+    };
+
+    // Collapsed segments can still be reached from the keyboard
+    let deepest = win.current.len();
+    match key {
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => win.breadcrumb = Some(selected.saturating_sub(1).max(1)),
+        KeyCode::Right | KeyCode::Char('l') => win.breadcrumb = Some((selected + 1).min(deepest)),
+        KeyCode::Home => win.breadcrumb = Some(1),
+        KeyCode::End => win.breadcrumb = Some(deepest),
+        KeyCode::Char(ch) if ch.is_ascii_digit() && ch != '0' => {
+            let depth = ch.to_digit(10).unwrap() as usize;
+            if depth <= deepest {
+                win.breadcrumb = None;
+                win.back_to(depth);
+            }
+        },
+        KeyCode::Enter => {
+            win.breadcrumb = None;
+            win.back_to(selected);
+        },
+        _ => win.breadcrumb = None,
+    }
+
+    print_header(win);
+    win.cursor.need_update = true;
+}
+
+// 7z can print a line per file, the last ones say what went wrong
+fn last_lines(report: &str) -> String {
+    let lines: Vec<&str> = report.lines().filter(|line| !line.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(ERROR_LINES)..].join("\n")
 }
 
 fn show_err_dialog(win: &mut Window, err: &str, exit: bool) {
@@ -249,148 +759,823 @@ fn show_err_dialog(win: &mut Window, err: &str, exit: bool) {
     }
 }
 
-fn close_dialog(win: &mut Window) {
-    print_header(win);
-    print_menu(win);
+fn get_temp_dir(win: &mut Window) -> Option<String> {
+    // Nested archives already live inside the workspace, their content goes next to them
+    let dir = if win.nested.is_empty() {
+        win.workspace.archive_dir(&win.get_path())
+    } else {
+        let dir = PathBuf::from(win.get_path() + ".d");
+        workspace::private_dir(&dir).map(|_| dir)
+    };
 
+    match dir {
+        Ok(dir) => Some(dir.to_string_lossy().into_owned()),
+        Err(err) => {
+            show_err_dialog(win, format!("Cannot create the temporary folder.\n{}", err).as_str(), false);
+            None
+        },
+    }
+}
+
+// Gives the terminal to something else, then takes it back and draws everything again
+fn away_from_screen<T>(win: &mut Window, away: impl FnOnce() -> T) -> T {
+    term::leave();
+    let result = term::away(away);
+    win.open_window();
+
+    // The terminal may have been resized meanwhile
+    if let Ok((width, height)) = terminal::size() {
+        win.set_size(width, height);
+    }
+    print_menu(win);
+    print_preview(win);
+    print_header(win);
     win.cursor.need_update = true;
-    win.on_dialog = false;
+    (&win.screen).queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
+    result
 }
 
-fn get_temp_dir(win: &mut Window) -> String {
-    if win.tmp_dir == "" {
-        let output = Command::new("mktemp")
-            .args(["-d", "--tmpdir", "content_7z.XXX"])
-            .output()
-            .expect("Getting tmp dir fail.");
-        let tmp_dir = String::from_utf8(output.stdout).expect("Cannot get the tmp dir.");
-        win.tmp_dir = String::from(&tmp_dir[..tmp_dir.len() - 1]);
+fn run_external(win: &mut Window, mut command: Command) {
+    // The program gets the real terminal, not the screen
+    if let Err(err) = away_from_screen(win, || command.status()) {
+        let program = command.get_program().to_string_lossy().into_owned();
+        show_err_dialog(win, format!("Cannot run '{}'.\n{}", program, err).as_str(), false);
     }
-    win.tmp_dir.clone() + "/" + win.get_path().as_str()
 }
 
-fn open_editor(win: &mut Window, file: PathBuf) {
-    let stdout = unsafe {
-        &mut (*win.writer)
+// Raw mode keeps Ctrl-Z from stopping the program by itself
+fn suspend(win: &mut Window) {
+    away_from_screen(win, || unsafe { libc::raise(libc::SIGTSTP) });
+}
+
+// Extracts the current folder into the workspace and runs $SHELL there, copies already extracted are kept
+fn open_shell(win: &mut Window) {
+    let Some(tmp_dir) = get_temp_dir(win) else {
+        return;
+    };
+    let at_root = win.current.len() == win.nested.last().map_or(0, |nested| nested.depth) + 1;
+    let member = (!at_root).then(|| String::from(&win.plain_member()[1..]));
+    let entry = Entry::Folder(win.get_current().clone());
+    let Some(dir) = safety::safe_join(Path::new(&tmp_dir), member.as_deref().unwrap_or_default()) else {
+        show_err_dialog(win, "This folder is unsafe, it is not extracted.", false);
+        return;
     };
 
-    stdout.queue(Clear(ClearType::Purge)).unwrap();
-    stdout.flush().unwrap();
+    let (files, blocked) = split_unsafe(win, member.as_deref(), &entry);
+    if files.is_empty() {
+        match workspace::private_dir(&dir) {
+            Ok(()) => run_shell(win, &dir),
+            Err(err) => show_err_dialog(win, format!("Cannot create '{}'.\n{}", dir.display(), err).as_str(), false),
+        }
+        return;
+    }
+
+    let mut switches = vec![String::from("x"), String::from("-aos"), format!("-o{}", tmp_dir)];
+    let blocked: Vec<&str> = blocked.iter().map(|(name, _)| name.as_str()).collect();
+    switches.extend(safety::exclusions(&blocked));
+    let members: Vec<String> = match &member {
+        _ if !blocked.is_empty() => files.iter().map(|(name, _)| name.clone()).collect(),
+        Some(member) => vec![member.clone()],
+        None => vec![],
+    };
+    let args = safety::command_args(switches, &win.get_path(), &members);
+
+    let shown = member.clone().unwrap_or_else(|| win.get_path());
+    let paths: Vec<PathBuf> = files.iter().filter_map(|(name, _)| safety::safe_join(Path::new(&tmp_dir), name)).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let task = Task::start(&format!("Extracting '{}' for the shell", shown), &args).map(|mut task| {
+        task.total_bytes = files.iter().map(|(_, info)| info.size).sum();
+        task.total_files = files.len();
+        // -aos keeps what is already there, so a cancelled run must not leave half written files behind
+        task.set_partial(new_paths(dir.clone(), &entry));
+        task
+    });
+    let job = NormalHandler::new(|win, situation, data| match situation {
+        HandleSituatonType::SUCESS(_) => {
+            safety::settle_links(Path::new(&data.0), &data.2, win.scheme.links);
+            run_shell(win, &data.1);
+        },
+        HandleSituatonType::TEXT(err) => {
+            show_err_dialog(win, format!("Cannot extract '{}'.\n{}", data.3, last_lines(&err)).as_str(), false);
+        },
+        _ => {},
+    }, (tmp_dir, dir, paths, shown));
+    start_task(win, task, job);
+}
+
+fn run_shell(win: &mut Window, dir: &Path) {
+    let shell = env::var("SHELL").unwrap_or_else(|_| String::from("sh"));
+    let mut command = Command::new(shell);
+    command.current_dir(dir);
+    run_external(win, command);
+}
 
+fn open_editor(win: &mut Window, file: PathBuf) {
     // Opening tmp_dir + path
     if win.scheme.editor.is_empty() {
         show_err_dialog(win, "Content-7z can not assumed any editor.\nDefine one in the config file:\n~/.config/content-7z.toml", false);
         return;
     }
 
-    let status = Command::new(win.scheme.editor.clone())
-        .arg(file.to_str().unwrap())
-        .status()
-        .expect("Couldnt open the editor");
+    let mut command = Command::new(win.scheme.editor.clone());
+    command.arg(file.to_str().unwrap());
+    run_external(win, command);
+}
+
+fn run_opener(win: &mut Window, command: &str, file: PathBuf) {
+    run_external(win, opener::shell(&opener::command_line(command, &file)));
+}
+
+fn open_choices(win: &Window, file: &Path) -> Vec<(String, OpenChoice)> {
+    let mime = opener::mime_type(file);
+    let mut choices: Vec<(String, OpenChoice)> = opener::matching(&win.scheme.openers, file, &mime)
+        .into_iter()
+        .map(|found| (found.command.clone(), OpenChoice::Command(found.command.clone())))
+        .collect();
+
+    if !win.scheme.editor.is_empty() {
+        choices.push((format!("{} (editor)", win.scheme.editor), OpenChoice::Editor));
+    }
+    if which("xdg-open").is_ok() {
+        choices.push((String::from("xdg-open"), OpenChoice::Command(String::from("xdg-open"))));
+    }
+    choices.push((String::from("built-in viewer"), OpenChoice::Viewer));
+    choices
+}
+
+fn open_with(win: &mut Window, file_name: String, file: PathBuf, choice: OpenChoice) {
+    match choice {
+        OpenChoice::Command(command) => run_opener(win, &command, file),
+        OpenChoice::Editor => open_editor(win, file),
+        OpenChoice::Viewer => open_viewer(win, file_name),
+    }
+}
+
+fn open_extracted(win: &mut Window, file_name: String, file: PathBuf) {
+    let mime = opener::mime_type(&file);
+    if let Some(found) = opener::matching(&win.scheme.openers, &file, &mime).first() {
+        let command = found.command.clone();
+        run_opener(win, &command, file);
+    } else if opener::is_text_mime(&mime) {
+        if win.scheme.editor.is_empty() {
+            open_viewer(win, file_name);
+        } else {
+            open_editor(win, file);
+        }
+    } else if which("xdg-open").is_ok() {
+        run_opener(win, "xdg-open", file);
+    } else {
+        open_editor(win, file);
+    }
+}
+
+fn show_open_with_dialog(win: &mut Window, file_name: String, file: PathBuf) {
+    let choices = open_choices(win, &file);
+    let labels = choices.iter()
+        .enumerate()
+        .map(|(index, (label, _))| if index < 9 { format!("{}. {}", index + 1, label) } else { label.clone() })
+        .collect();
+    let select = Select::new(&format!("Open '{}' with:", &file_name[1..]), labels);
+
+    let job = NormalHandler::new(|win, situation, data| {
+        if let HandleSituatonType::CHOICE(index) = situation {
+            if let Some((_, choice)) = data.2.get(index) {
+                open_with(win, data.0.clone(), data.1.clone(), choice.clone());
+            }
+        }
+    }, (file_name, file, choices));
+
+    show_select_dialog(win, select, job);
+}
 
-    if status.code().expect("Cannot stablish connection with the editor") != 0 {
-        // TODO
+// Where a member goes in the tmp dir, None after telling why it cannot be extracted
+fn temp_path(win: &mut Window, tmp_dir: &str, file_name: &str) -> Option<PathBuf> {
+    if let Some(hazard) = safety::hazard(win.archive_info(), &file_name[1..]) {
+        show_err_dialog(win, format!("'{}' is not extracted, it is unsafe: {}.", &file_name[1..], hazard.describe()).as_str(), false);
+        return None;
     }
+    safety::safe_join(Path::new(tmp_dir), &file_name[1..])
+}
 
-    let stdout = unsafe {
-        &mut (*win.writer)
+fn extract_file<T: Handler + 'static>(win: &mut Window, tmp_dir: &str, file_name: &str, choice: Choice, handler: T) {
+    let Some(mut target) = temp_path(win, tmp_dir, file_name) else {
+        return;
     };
+    let name = target.file_name().unwrap_or_default().to_string_lossy().into_owned();
 
-    win.open_window();
-    stdout.queue(Clear(ClearType::Purge)).unwrap();
-    stdout.flush().unwrap();
+    // Making the path directories, remembered to remove them if the extraction stops
+    let output_dir = target.parent().map(Path::to_path_buf).unwrap_or_default();
+    let made_dirs = task::missing_dirs(&target);
+    if let Err(err) = fs::create_dir_all(&output_dir) {
+        show_err_dialog(win, format!("Cannot create '{}'.\n{}", output_dir.display(), err).as_str(), false);
+        return;
+    }
 
-    print_menu(win);
-    print_header(win);
+    // Extracting the file to: tmp_dir + dir
+    let win_path = win.get_path();
+    let output_path = format!("-o{}", output_dir.display());
+    let mode = match choice {
+        Choice::Overwrite => "-aoa",
+        Choice::Skip => "-aos",
+        Choice::Rename => "-aou",
+    };
+    let extractor_args = vec!["e", output_path.as_str(), mode, safety::LITERAL_NAMES, "--", win_path.as_str(), &file_name[1..]];
+    if choice == Choice::Rename {
+        target = conflict::renamed(&target);
+    }
 
-    stdout.queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
-    stdout.flush().unwrap();
+    let size = match win.get_selected() {
+        Some(entry) => win.entry_size(entry),
+        None => 0,
+    };
+    let task = Task::start(&format!("Extracting '{}'", name), &extractor_args).map(|mut task| {
+        task.total_bytes = size;
+        task.total_files = 1;
+        task.set_partial(vec![target]);
+        task.made_dirs = made_dirs;
+        task
+    });
+    start_task(win, task, handler);
 }
 
-fn extract_an_open_file(win: &mut Window, tmp_dir: String, file_name: String, file: PathBuf, overwrite: bool) {
-    let path = if let Some(path) = file_name.rfind("/") {
-        vec![&file_name[..path + 1], &file_name[path + 1..]]
-    } else {
-        vec!["/", file_name.as_str()]
+fn base_name(file_name: &str) -> &str {
+    file_name.rsplit('/').next().unwrap_or(file_name)
+}
+
+// Only for the archive given to the program, nested ones are extracted again every session
+fn open_cache(win: &Window) -> Option<Cache> {
+    if !win.scheme.cache || !win.nested.is_empty() {
+        return None;
+    }
+    Cache::open(win.scheme.cache_size)
+}
+
+// The key of the member once it is known, and whether the cached copy was used
+type CacheLookup = Arc<Mutex<Option<(String, bool)>>>;
+
+fn extract_an_open_file(win: &mut Window, tmp_dir: String, file_name: String, file: PathBuf, choice: Choice, then: OpenJob) {
+    // The renamed copy is the one opened
+    let file = match choice {
+        Choice::Rename => conflict::renamed(&file),
+        _ => file,
+    };
+    let Some(cache) = open_cache(win) else {
+        extract_for_opening(win, tmp_dir, file_name, file, choice, then, None);
+        return;
+    };
+
+    // The CRC needs a 7z run and a copy from an earlier session can be large, both are done in the background
+    let archive = win.get_path();
+    let member = String::from(&file_name[1..]);
+    let info = win.archive_info().get(&member).cloned().unwrap_or_default();
+    let name = String::from(base_name(&file_name));
+    let size = info.size;
+    let lookup = CacheLookup::default();
+    let (found, source, target) = (lookup.clone(), cache.clone(), file.clone());
+    let mut task = Task::spawn(&format!("Looking for '{}' in the cache", name), move |progress, cancelled| {
+        let crc = cache::crc(&archive, &member);
+        let Some(key) = cache::key(Path::new(&archive), &member, &info, crc.as_deref()) else {
+            return Ok(());
+        };
+        let copied = source.get(&key, &name, info.size).is_some_and(|copy| {
+            let report = |done| {
+                let _ = progress.send(Progress::bytes(done, info.size, &name));
+            };
+            target.parent().is_some_and(|parent| fs::create_dir_all(parent).is_ok())
+                && cache::copy(&copy, &target, &report, cancelled).is_ok()
+        });
+        *found.lock().unwrap() = Some((key, copied));
+        Ok(())
+    });
+    task.total_bytes = size;
+    task.total_files = 1;
+
+    let job = NormalHandler::new(|win, situation, data| {
+        if let HandleSituatonType::SUCESS(_) = situation {
+            let (tmp_dir, file_name, file, choice, then, cache, lookup) = data.clone();
+            let found = lookup.lock().unwrap().take();
+            match found {
+                Some((_, true)) => then(win, file_name, file),
+                Some((key, false)) => extract_for_opening(win, tmp_dir, file_name, file, choice, then, Some((cache, key))),
+                None => extract_for_opening(win, tmp_dir, file_name, file, choice, then, None),
+            }
+        }
+    }, (tmp_dir, file_name, file, choice, then, cache, lookup));
+    start_task(win, Ok(task), job);
+}
+
+fn extract_for_opening(win: &mut Window, tmp_dir: String, file_name: String, file: PathBuf, choice: Choice, then: OpenJob, cached: Option<(Cache, String)>) {
+    let job = NormalHandler::new(|win, situation, data| match situation {
+        // A link the policy does not allow is removed before anything can follow it
+        HandleSituatonType::SUCESS(_) if safety::settle_links(Path::new(&data.3), std::slice::from_ref(&data.1), win.scheme.links) > 0 => {
+            show_err_dialog(win, format!("'{}' is a link the \"symlinks\" policy does not allow, it was removed.", &data.0[1..]).as_str(), false);
+        },
+        HandleSituatonType::SUCESS(_) => match data.4.clone() {
+            Some((cache, key)) => keep_in_cache(win, cache, key, data.0.clone(), data.1.clone(), data.2),
+            None => (data.2)(win, data.0.clone(), data.1.clone()),
+        },
+        HandleSituatonType::TEXT(err) => {
+            show_err_dialog(win, format!("Cannot extract '{}'.\n{}", &data.0[1..], last_lines(&err)).as_str(), false);
+        },
+        _ => {},
+    }, (file_name.clone(), file, then, tmp_dir.clone(), cached));
+    extract_file(win, &tmp_dir, &file_name, choice, job);
+}
+
+// The copy takes as long as a second extraction would, so it is a task of its own
+fn keep_in_cache(win: &mut Window, cache: Cache, key: String, file_name: String, file: PathBuf, then: OpenJob) {
+    let name = String::from(base_name(&file_name));
+    let size = fs::metadata(&file).map_or(0, |metadata| metadata.len());
+    let source = file.clone();
+    let mut task = Task::spawn(&format!("Keeping '{}' in the cache", name), move |progress, cancelled| {
+        let report = |done| {
+            let _ = progress.send(Progress::bytes(done, size, &name));
+        };
+        cache.put(&key, &name, &source, &report, cancelled).map_err(|err| err.to_string())
+    });
+    task.total_bytes = size;
+    task.total_files = 1;
+
+    // The cache is only a shortcut, the file opens even if it cannot be kept
+    let job = NormalHandler::new(|win, _, data| (data.2)(win, data.0.clone(), data.1.clone()), (file_name, file, then));
+    start_task(win, Ok(task), job);
+}
+
+fn open_archive(win: &mut Window, file_name: String) {
+    let Some(tmp_dir) = get_temp_dir(win) else {
+        return;
+    };
+    let Some(file) = temp_path(win, &tmp_dir, &file_name) else {
+        return;
     };
 
-    // Making the path directories
-    let mkdir_status = Command::new("mkdir")
-        .args(["-p", (tmp_dir.clone() + path[0]).as_str()])
-        .stdout(Stdio::null())
-        .status().expect("Cannot communicate with the terminal");
+    // The archive is only read, so an already extracted copy is always valid
+    if file.exists() {
+        open_nested_archive(win, file_name, file);
+    } else {
+        extract_an_open_file(win, tmp_dir, file_name, file, Choice::Overwrite, open_nested_archive);
+    }
+}
 
-    if mkdir_status.code().expect("Cannot communicate with the 'mkdir' command") != 0 {
-        // TODO
+fn open_nested_archive(win: &mut Window, file_name: String, file: PathBuf) {
+    let manager = ZipManager::process(file.to_str().unwrap());
+    if !manager.err.is_empty() || manager.res_code != 0 {
+        show_err_dialog(win, format!("Cannot open '{}' as an archive.\n{}", &file_name[1..], last_lines(&manager.err)).as_str(), false);
         return;
     }
 
-    // Extracting the file to: tmp_dir + path[0]
-    let win_path = win.get_path();
-    let output_path = format!("-o{}/{}", tmp_dir, path[0]);
+    let name = match file_name.rfind('/') {
+        Some(index) => &file_name[index + 1..],
+        None => file_name.as_str(),
+    };
+    win.assign_nested_manager(manager, name);
+}
+
+fn open_file(win: &mut Window, file_name: String, then: OpenJob) {
+    let Some(tmp_dir) = get_temp_dir(win) else {
+        return;
+    };
+    let Some(file) = temp_path(win, &tmp_dir, &file_name) else {
+        return;
+    };
+
+    // A copy from before is left, re-extracted or kept next to the new one as the policy says
+    let member = String::from(&file_name[1..]);
+    let info = win.archive_info().get(&member).cloned().unwrap_or_default();
+    let Some(found) = conflict::find(Path::new(&tmp_dir), vec![(member, info)]).pop() else {
+        extract_an_open_file(win, tmp_dir, file_name, file, Choice::Overwrite, then);
+        return;
+    };
 
-    let mut extractor_args = vec!["e", win_path.as_str(), &file_name[1..], output_path.as_str()];
-    if overwrite {
-        extractor_args.push("-y");
+    match found.choice(win.scheme.overwrite) {
+        Some(choice) => open_resolved(win, tmp_dir, file_name, file, choice, then),
+        None => {
+            let select = conflict_select(&found, 0, true);
+            let job = NormalHandler::new(|win, situation, data| {
+                if let HandleSituatonType::CHOICE(index) = situation {
+                    let (policy, always) = conflict_choice(index);
+                    if always {
+                        win.scheme.overwrite = policy;
+                    }
+                    let choice = data.4.choice(policy).unwrap();
+                    open_resolved(win, data.2.clone(), data.0.clone(), data.1.clone(), choice, data.3);
+                }
+            }, (file_name, file, tmp_dir, then, found));
+            show_select_dialog(win, select, job);
+        },
     }
-    let extract_status = Command::new("7z")
-        .args(extractor_args)
-        .stdout(Stdio::null())
-        .status().expect("Cannot execute the extractor.");
+}
 
-    if extract_status.code().expect("Cannot extract the file from the compress file.") != 0 {
-        // TODO
-        return
+fn open_resolved(win: &mut Window, tmp_dir: String, file_name: String, file: PathBuf, choice: Choice, then: OpenJob) {
+    match choice {
+        Choice::Skip => then(win, file_name, file),
+        _ => extract_an_open_file(win, tmp_dir, file_name, file, choice, then),
     }
+}
 
-    open_editor(win, file);
+// Returns true when the action left a dialog or a viewer waiting for input
+fn run_action(win: &mut Window, action: Action) -> bool {
+    match action {
+        Action::Quit => {},
+        Action::Up => win.move_up(),
+        Action::Down => win.move_down(),
+        Action::Right => win.move_right(),
+        Action::Left => win.move_left(),
+        Action::ScrollLeft => win.scroll_left(),
+        Action::ScrollRight => win.scroll_right(),
+        Action::TogglePreview => win.toggle_preview(),
+        Action::Mark => {
+            win.toggle_mark();
+            win.move_down();
+        },
+        Action::ShowPath => {
+            let path = win.plain_current();
+            show_dialog(win, path);
+        },
+        Action::Sort => win.set_sort(win.sort.next()),
+        Action::Filter => {
+            let job = NormalHandler::new(|win, situation, _| {
+                if let HandleSituatonType::TEXT(filter) = situation {
+                    win.set_filter(&filter);
+                }
+            }, ());
+
+            show_input_dialog(win, "Show the names that contain:", "filter", job);
+            return true;
+        },
+        Action::Help => {
+            win.help = Some(0);
+            print_help(win);
+            return true;
+        },
+        Action::Breadcrumbs => {
+            win.breadcrumb = Some(win.current.len());
+            print_header(win);
+            return true;
+        },
+        Action::Command => {
+            win.palette.open();
+            print_palette(win);
+            return true;
+        },
+        Action::View => {
+            if let Some(Entry::File(file_name)) = win.get_selected() {
+                let path = win.plain_member() + "/" + file_name;
+                open_viewer(win, path);
+            }
+        },
+        Action::Open => {
+            if let Some(Entry::File(file_name)) = win.get_selected() {
+                let path = win.plain_member() + "/" + file_name;
+                // Nothing else could open it, no need to extract it
+                let has_opener = win.scheme.openers.iter().any(|found| found.matches_name(file_name));
+                if win.scheme.editor.is_empty() && !has_opener && which("xdg-open").is_err() {
+                    open_viewer(win, path);
+                    return true;
+                }
+
+                let message = format!("Open '{}'?", path);
+
+                let job = NormalHandler::new(|win, situation, file_name| {
+                    if let HandleSituatonType::SUCESS(direct) = situation {
+                        if direct {
+                            open_file(win, file_name.clone(), open_extracted);
+                        }
+                    }
+                }, path);
+
+                show_multiple_choice_dialog(win, message, job);
+                return true;
+            }
+        },
+        Action::Pipe => {
+            if let Some(Entry::File(file_name)) = win.get_selected() {
+                let path = win.plain_member() + "/" + file_name;
+                let quest = format!("Pipe '{}' to:", &path[1..]);
+
+                let job = NormalHandler::new(|win, situation, file_name| {
+                    if let HandleSituatonType::TEXT(command_line) = situation {
+                        if !command_line.trim().is_empty() {
+                            pipe_file(win, file_name.clone(), command_line);
+                        }
+                    }
+                }, path);
+
+                show_input_dialog(win, &quest, "pipe", job);
+                return true;
+            }
+        },
+        Action::OpenWith => {
+            if let Some(Entry::File(file_name)) = win.get_selected() {
+                let path = win.plain_member() + "/" + file_name;
+                open_file(win, path, show_open_with_dialog);
+                return true;
+            }
+        },
+        Action::Shell => {
+            open_shell(win);
+            return win.is_modal();
+        },
+        Action::Suspend => suspend(win),
+        Action::Back => win.back_current(),
+        Action::Enter => {
+            match win.get_selected() {
+                Some(Entry::Folder(dir)) => win.set_current(dir.clone()),
+                Some(Entry::File(file_name)) if ZipManager::is_archive(file_name) => {
+                    let path = win.plain_member() + "/" + file_name;
+                    open_archive(win, path);
+                },
+                _ => {},
+            }
+        },
+    }
+    false
 }
 
-fn open_file(win: &mut Window, file_name: String) {
-    // Getting the tmp dir for this session
-    let tmp_dir = get_temp_dir(win);
-    // Getting a file refence
-    let file = PathBuf::from(tmp_dir.clone() + "/" + file_name.as_str());
-    if !win.scheme.always_overwrite && file.exists() {
-        let job = NormalHandler::new(|win, situation, data| {
-            if let HandleSituatonType::SUCESS(_) = situation {
-                extract_an_open_file(win, data.2.clone(), data.0.clone(), data.1.clone(), true);
-            } else if let HandleSituatonType::DENIED = situation {
-                open_editor(win, data.1.clone());
+fn mouse_event(win: &mut Window, mouse: MouseEvent) -> bool {
+    match mouse.kind {
+        MouseEventKind::ScrollDown => win.scroll_by(3),
+        MouseEventKind::ScrollUp => win.scroll_by(-3),
+        MouseEventKind::Down(MouseButton::Left) if mouse.row == 1 => {
+            // Clicking a folder of the header path goes back to it
+            let column = usize::from(mouse.column.saturating_sub(1));
+            if let Some(depth) = breadcrumbs::depth_at(&win.crumbs(), column) {
+                win.back_to(depth);
             }
-        }, (file_name, file, tmp_dir));
-        show_multiple_choice_dialog(win, String::from("File already extracted, pressent on cache.\nExtract it again?"), job);
-        return;
+        },
+        MouseEventKind::Down(MouseButton::Left) => {
+            let rows = 4..4 + win.visible_rows();
+            if !rows.contains(&mouse.row) || mouse.column >= win.list_width() {
+                return false;
+            }
+            if usize::from(mouse.row - 4) + win.scroll_y >= win.get_current().content.len() {
+                return false;
+            }
+
+            if win.click(mouse.row) {
+                let action = match win.get_selected() {
+                    Some(Entry::File(file_name)) if !ZipManager::is_archive(file_name) => Action::Open,
+                    _ => Action::Enter,
+                };
+                return run_action(win, action);
+            }
+        },
+        _ => {},
+    }
+    false
+}
+
+// Extraction without the browser, the conflicts are solved by the policy given or make it fail
+fn extract_to(args: &cli::Args, dir: &str) -> i32 {
+    let manager = ZipManager::process(&args.archive);
+    if !manager.err.is_empty() || manager.res_code != 0 {
+        eprintln!("Process Error: {}", manager.err.trim());
+        return -1;
+    }
+
+    let scheme = Scheme::from(config::load());
+    let info = manager.get_info();
+    let mut members: Vec<String> = args.members.iter().map(|member| String::from(member.trim_end_matches('/'))).collect();
+    let inside = |name: &str| members.is_empty() || members.iter().any(|member| {
+        name == member || name.strip_prefix(member.as_str()).is_some_and(|rest| rest.starts_with('/'))
+    });
+
+    // Unsafe members are left out, naming the safe files one by one
+    let mut blocked: Vec<&String> = info.keys()
+        .filter(|name| inside(name) && safety::hazard(&info, name).is_some())
+        .collect();
+    blocked.sort();
+    for name in &blocked {
+        eprintln!("'{}' left out, it is unsafe: {}", name, safety::hazard(&info, name).unwrap().describe());
+    }
+    let files: Vec<(String, EntryInfo)> = manager.files().into_iter()
+        .filter(|(name, _)| inside(name) && safety::hazard(&info, name).is_none())
+        .collect();
+    if !blocked.is_empty() {
+        if files.is_empty() {
+            return 1;
+        }
+        members = files.iter().map(|(name, _)| name.clone()).collect();
+    }
+
+    let policy = args.policy.unwrap_or(scheme.overwrite);
+    let conflicts = conflict::find(Path::new(dir), files.clone());
+    if policy == Policy::Ask && !conflicts.is_empty() {
+        for conflict in &conflicts {
+            eprintln!("'{}' already exists", conflict.path.display());
+        }
+        eprintln!("Choose what to do with them with --overwrite, --skip, --rename or --keep-newer");
+        return 1;
+    }
+
+    let mut resolution = Resolution::default();
+    for conflict in &conflicts {
+        resolution.add(conflict, conflict.choice(policy).unwrap());
+    }
+
+    let runs = [Some(resolution.args(&args.archive, dir, &members, &blocked)), resolution.rename_args(&args.archive, dir, &blocked)];
+    for run in runs.into_iter().flatten() {
+        match Command::new("7z").arg(&run[0]).arg("-bso0").args(&run[1..]).status() {
+            Ok(status) if status.success() => {},
+            Ok(status) => return status.code().unwrap_or(-1),
+            Err(err) => {
+                eprintln!("Process Error: {}", err);
+                return -1;
+            },
+        }
     }
 
-    extract_an_open_file(win, tmp_dir, file_name, file, false);
-} 
+    let extracted: Vec<PathBuf> = files.iter()
+        .filter(|(name, _)| !resolution.is_resolved(name))
+        .filter_map(|(name, _)| safety::safe_join(Path::new(dir), name))
+        .chain(resolution.rename.iter().map(|(_, path)| path.clone()))
+        .collect();
+    let links = safety::settle_links(Path::new(dir), &extracted, scheme.links);
+
+    let mut summary = vec![resolution.summary()];
+    if !blocked.is_empty() {
+        summary.push(format!("{} unsafe left out", blocked.len()));
+    }
+    if links > 0 {
+        summary.push(format!("{} link{} removed", links, if links == 1 { "" } else { "s" }));
+    }
+    summary.retain(|part| !part.is_empty());
+    if !summary.is_empty() {
+        println!("{}", summary.join(", "));
+    }
+    0
+}
+
+fn clear_cache() -> i32 {
+    let Some(cache) = Cache::open(0) else {
+        eprintln!("There is no cache folder");
+        return 1;
+    };
+    match cache.clear() {
+        Ok((files, size)) => {
+            println!("Removed {} file{} ({}) from '{}'", files, if files == 1 { "" } else { "s" }, human_size(size), cache.dir.display());
+            0
+        },
+        Err(err) => {
+            eprintln!("Cannot clear '{}': {}", cache.dir.display(), err);
+            1
+        },
+    }
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage:\n\t{} {{7zip file}}", &args[0]);
-        exit(-1);
+    let parsed = match cli::parse(&args[1..]) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{}\n{}", err, cli::usage(&args[0]));
+            exit(-1);
+        },
+    };
+    if parsed.clear_cache {
+        exit(clear_cache());
+    }
+    if let Some(dir) = &parsed.extract {
+        exit(extract_to(&parsed, dir));
     }
 
-    let mut stdout = stdout().lock();
+    let listing = match Listing::start(&parsed.archive) {
+        Ok(listing) => listing,
+        Err(err) => {
+            eprintln!("Process Error: {}", err);
+            exit(-1);
+        },
+    };
 
-    let mut win = Window::new(&mut stdout, config::load());
-    let manager = ZipManager::process(&args[1]);
-    if manager.err != "" {
-        eprintln!("Process Error: {}", manager.err);
-        exit(-1);
-    }
+    term::restore_on_exit();
 
-    win.assing_manager(manager);
+    // The list fills while the archive is read
+    let (width, height) = terminal::size().unwrap();
+    let mut win = Window::new(config::load(), width, height);
+    if let Some(policy) = parsed.policy {
+        win.scheme.overwrite = policy;
+    }
+    win.assign_path(parsed.archive.clone());
+    win.assign_root(Folder::new("."));
+    win.loading = Some(listing);
+    win.open_window();
+    let mut stdout = stdout().lock();
+    let mut failed = None;
 
-    print_header(&mut win);
-    print_menu(&mut win);
-    stdout.queue(MoveTo(1, 4)).unwrap();
+    print_header(&win);
+    print_menu(&win);
+    (&win.screen).queue(MoveTo(1, 4)).unwrap();
 
     'mainLoop:
     loop {
-        while event::poll(Duration::ZERO).unwrap() {
+        // Whatever was drawn is shown before waiting, also when an action skipped the rest of the loop
+        win.screen.flush_to(&mut stdout).unwrap();
+
+        // Sleeps until there is input or something on screen has to change
+        let mut timeout = win.next_wakeup();
+        while timeout.map_or(true, |timeout| event::poll(timeout).unwrap()) {
+            timeout = Some(Duration::ZERO);
+            if win.task.is_some() {
+                match event::read().unwrap() {
+                    Event::Key(key) if key.code == KeyCode::Esc => cancel_task(&mut win),
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        close_dialog(&mut win);
+                        print_task(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            if win.viewer.is_some() {
+                match event::read().unwrap() {
+                    Event::Key(key) => viewer_key(&mut win, key.code),
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        if let Some(viewer) = win.viewer.as_mut() {
+                            viewer.width = width;
+                            viewer.height = height;
+                        }
+                        print_viewer(&mut win);
+                    },
+                    Event::Mouse(mouse) => {
+                        if let Some(viewer) = win.viewer.as_mut() {
+                            match mouse.kind {
+                                MouseEventKind::ScrollDown => viewer.scroll_down(3),
+                                MouseEventKind::ScrollUp => viewer.scroll_up(3),
+                                _ => continue,
+                            }
+                        }
+                        print_viewer(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            if win.input.is_some() {
+                match event::read().unwrap() {
+                    Event::Key(key) => input_key(&mut win, &key),
+                    Event::Paste(pasted) => input_paste(&mut win, &pasted),
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        print_menu(&win);
+                        print_header(&win);
+                        print_input(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            if win.select.is_some() {
+                match event::read().unwrap() {
+                    Event::Key(key) => select_key(&mut win, &key),
+                    Event::Mouse(mouse) => match mouse.kind {
+                        MouseEventKind::ScrollDown => select_key(&mut win, &KeyEvent::from(KeyCode::Down)),
+                        MouseEventKind::ScrollUp => select_key(&mut win, &KeyEvent::from(KeyCode::Up)),
+                        _ => {},
+                    },
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        close_dialog(&mut win);
+                        print_select(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            if win.breadcrumb.is_some() {
+                if let Event::Key(key) = event::read().unwrap() {
+                    breadcrumb_key(&mut win, key.code);
+                }
+                continue;
+            }
+            if win.palette.visible {
+                match event::read().unwrap() {
+                    Event::Key(key) if palette_key(&mut win, key.code) => break 'mainLoop,
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        print_palette(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            if win.help.is_some() {
+                match event::read().unwrap() {
+                    Event::Key(key) => help_key(&mut win, key.code),
+                    Event::Mouse(mouse) => match mouse.kind {
+                        MouseEventKind::ScrollDown => help_key(&mut win, KeyCode::Down),
+                        MouseEventKind::ScrollUp => help_key(&mut win, KeyCode::Up),
+                        _ => {},
+                    },
+                    Event::Resize(width, height) => {
+                        win.set_size(width, height);
+                        print_help(&mut win);
+                    },
+                    _ => {},
+                }
+                continue;
+            }
             if win.on_dialog {
                 if let Event::Key(key) = event::read().unwrap() {
                     close_dialog(&mut win);
@@ -408,49 +1593,13 @@ fn main() {
             }
             match event::read().unwrap() {
                 Event::Key(ev) => {
-                    match ev.code {
-                        KeyCode::Esc | KeyCode::Char('q') => break 'mainLoop,
-                        KeyCode::Up => win.move_up(),
-                        KeyCode::Down => win.move_down(),
-                        KeyCode::Right => win.move_right(),
-                        KeyCode::Left => win.move_left(),
-                        KeyCode::Char('t') => {
-                            show_err_dialog(&mut win, "Hello", true);
-                        },
-                        KeyCode::Char('p') => {
-                            let path = win.plain_current();
-                            show_dialog(&mut win, path);
-                        },
-                        KeyCode::Char('o') => {
-                            if usize::from(win.cursor.y - 4 + win.scroll_y) < win.get_current().content.len() {
-                                if let Entry::File(file_name) = &win.get_current().content[usize::from(win.cursor.y - 4 + win.scroll_y)] {
-                                    let path = win.plain_current() + "/" + file_name;
-                                    let message = format!("Open '{}'?", path);
-
-                                    let job = NormalHandler::new(|win, situation, file_name| {
-                                        if let HandleSituatonType::SUCESS(direct) = situation {
-                                            if direct {
-                                                open_file(win, file_name.clone());
-                                            }
-                                        }
-                                    }, path);
-
-                                    show_multiple_choice_dialog(&mut win, message, job);
-                                    continue 'mainLoop;
-                                }
-                            }
-                        }
-                        KeyCode::Backspace => win.back_current(),
-                        KeyCode::Enter => {
-                            if usize::from(win.cursor.y - 4 + win.scroll_y) < win.get_current().content.len() {
-                                if let Entry::Folder(dir) = &win.get_current().content[usize::from(win.cursor.y - 4 + win.scroll_y)] {
-                                    win.set_current(dir.clone());
-                                }
-                            }
-                        },
-                        _ => {}
+                    match win.keymap.action(&ev) {
+                        Some(Action::Quit) => break 'mainLoop,
+                        Some(action) if run_action(&mut win, action) => continue 'mainLoop,
+                        _ => {},
                     }
                 },
+                Event::Mouse(mouse) if mouse_event(&mut win, mouse) => continue 'mainLoop,
                 Event::Resize(width, height) => {
                     win.set_size(width, height);
 
@@ -462,29 +1611,57 @@ fn main() {
             }
         }
 
+        if let Some(err) = update_loading(&mut win) {
+            failed = Some(err);
+            break 'mainLoop;
+        }
+        update_task(&mut win);
+        update_viewer(&mut win);
+        update_preview(&mut win);
+
+        let modal = win.is_modal();
+        if !modal && win.marquee_tick() {
+            print_row(&win, win.cursor.y);
+            win.cursor.need_update = true;
+        }
+
         if win.scroll_change {
             win.scroll_change = false;
-            print_menu(&mut win);
-            stdout.queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
+            print_menu(&win);
+            print_preview(&win);
+            (&win.screen).queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
         }
 
         if win.path_change {
             win.path_change = false;
             print_header(&win);
-            stdout.queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
+            (&win.screen).queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
+        }
+
+        if win.message.is_some() && win.get_message().is_none() {
+            win.message = None;
+            print_status(&win);
+            win.cursor.need_update = true;
         }
 
         if win.cursor.need_update {
             win.cursor.need_update = false;
-            let new_y: u16 = (win.get_current().content.len() + 3).try_into().unwrap();
+            if !modal {
+                print_status(&win);
+            }
+            // Only the rows up to the last entry can hold the cursor, however long the folder is
+            let new_y = u16::try_from(win.get_current().content.len() + 3).unwrap_or(u16::MAX);
             if !win.on_dialog && win.cursor.y > new_y && new_y != 3 {
                 win.set_cursor(win.cursor.x, new_y);
             }
-            stdout.queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
+            (&win.screen).queue(MoveTo(win.cursor.x, win.cursor.y)).unwrap();
         }
+    }
 
-        stdout.flush().unwrap();
-
-        thread::sleep(Duration::from_millis(30));
+    // The terminal has to be restored before the error can be read
+    drop(win);
+    if let Some(err) = failed {
+        eprintln!("Process Error: {}", err.trim());
+        exit(-1);
     }
 }
@@ -0,0 +1,72 @@
+use crate::window::text::{self, ELLIPSIS};
+
+pub const SEPARATOR: &str = " › ";
+
+pub struct Crumb {
+    pub text: String,
+    // None for the collapsed middle segments
+    pub depth: Option<usize>,
+    pub column: usize,
+}
+
+impl Crumb {
+    pub fn width(&self) -> usize {
+        text::width(&self.text)
+    }
+}
+
+fn total_width(texts: &[(String, Option<usize>)]) -> usize {
+    let separators = texts.len().saturating_sub(1) * text::width(SEPARATOR);
+    texts.iter().map(|(name, _)| text::width(name)).sum::<usize>() + separators
+}
+
+// The first segment is the archive, every segment after it one folder deeper
+pub fn layout(names: &[String], width: usize) -> Vec<Crumb> {
+    let all: Vec<(String, Option<usize>)> = names.iter()
+        .enumerate()
+        .map(|(index, name)| (name.clone(), Some(index + 1)))
+        .collect();
+
+    let mut shown = all.clone();
+    if total_width(&shown) > width && all.len() > 1 {
+        // Middle segments collapse first, the archive and the current folder stay
+        let mut keep = all.len() - 1;
+        loop {
+            shown = vec![all[0].clone(), (String::from(ELLIPSIS), None)];
+            shown.extend(all[all.len() - keep..].iter().cloned());
+            if total_width(&shown) <= width || keep == 1 {
+                break;
+            }
+            keep -= 1;
+        }
+
+        if total_width(&shown) > width {
+            shown = vec![(String::from(ELLIPSIS), None), all[all.len() - 1].clone()];
+        }
+    }
+
+    if total_width(&shown) > width {
+        let last = shown.len() - 1;
+        let rest = total_width(&shown) - text::width(&shown[last].0);
+        // Too narrow for anything else, only the current folder is left
+        if rest >= width {
+            shown = vec![shown[last].clone()];
+            shown[0].0 = text::truncate_left(&shown[0].0, width);
+        } else {
+            shown[last].0 = text::truncate_left(&shown[last].0, width - rest);
+        }
+    }
+
+    let mut column = 0;
+    shown.into_iter().map(|(name, depth)| {
+        let crumb = Crumb { text: name, depth, column };
+        column += crumb.width() + text::width(SEPARATOR);
+        crumb
+    }).collect()
+}
+
+pub fn depth_at(crumbs: &[Crumb], column: usize) -> Option<usize> {
+    crumbs.iter()
+        .find(|crumb| column >= crumb.column && column < crumb.column + crumb.width())
+        .and_then(|crumb| crumb.depth)
+}
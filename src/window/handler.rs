@@ -7,6 +7,9 @@ pub enum HandleSituatonType {
     SUCESS(bool),
     DENIED,
     KEY(char),
+    TEXT(String),
+    // Index of the item chosen in a list
+    CHOICE(usize),
 }
 
 pub trait Handler {
@@ -0,0 +1,225 @@
+use crate::window::text;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub struct Input {
+    pub quest: String,
+    pub value: String,
+    // Byte offset in value, always at the start of a character
+    pub cursor: usize,
+    // What the value is for, values with the same purpose share their history
+    pub purpose: Option<&'static str>,
+    pub history: Vec<String>,
+    pub history_index: Option<usize>,
+    // What was being written before going through the history
+    draft: String,
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+impl Input {
+    pub fn new(quest: &str) -> Self {
+        Self {
+            quest: String::from(quest),
+            value: String::new(),
+            cursor: 0,
+            purpose: None,
+            history: vec![],
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn with_history(quest: &str, purpose: &'static str, history: Vec<String>) -> Self {
+        let mut input = Self::new(quest);
+        input.purpose = Some(purpose);
+        input.history = history;
+        input
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.value = String::from(value);
+        self.cursor = self.value.len();
+    }
+
+    pub fn insert(&mut self, ch: char) {
+        self.value.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+        self.history_index = None;
+    }
+
+    // Pasted text is a single line
+    pub fn insert_str(&mut self, pasted: &str) {
+        let line: String = pasted.chars()
+            .map(|ch| if ch == '\n' || ch == '\t' { ' ' } else { ch })
+            .filter(|ch| !ch.is_control())
+            .collect();
+        self.value.insert_str(self.cursor, &line);
+        self.cursor += line.len();
+        self.history_index = None;
+    }
+
+    fn previous(&self) -> usize {
+        self.value[..self.cursor].grapheme_indices(true)
+            .next_back()
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    fn next(&self) -> usize {
+        self.value[self.cursor..].graphemes(true)
+            .next()
+            .map(|grapheme| self.cursor + grapheme.len())
+            .unwrap_or(self.cursor)
+    }
+
+    fn word_start(&self) -> usize {
+        let mut position = self.cursor;
+        let mut in_word = false;
+        for (index, grapheme) in self.value[..self.cursor].grapheme_indices(true).rev() {
+            if in_word && !is_word(grapheme) {
+                break;
+            }
+            in_word |= is_word(grapheme);
+            position = index;
+        }
+        position
+    }
+
+    fn word_end(&self) -> usize {
+        let mut position = self.cursor;
+        let mut in_word = false;
+        for (index, grapheme) in self.value[self.cursor..].grapheme_indices(true) {
+            if in_word && !is_word(grapheme) {
+                break;
+            }
+            in_word |= is_word(grapheme);
+            position = self.cursor + index + grapheme.len();
+        }
+        position
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.previous();
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = self.next();
+    }
+
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    pub fn word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.value.len();
+    }
+
+    fn remove(&mut self, start: usize, end: usize) {
+        self.value.replace_range(start..end, "");
+        self.cursor = start;
+        self.history_index = None;
+    }
+
+    pub fn backspace(&mut self) {
+        self.remove(self.previous(), self.cursor);
+    }
+
+    pub fn delete(&mut self) {
+        self.remove(self.cursor, self.next());
+    }
+
+    pub fn delete_word(&mut self) {
+        self.remove(self.word_start(), self.cursor);
+    }
+
+    pub fn delete_to_start(&mut self) {
+        self.remove(0, self.cursor);
+    }
+
+    pub fn delete_to_end(&mut self) {
+        let cursor = self.cursor;
+        self.remove(cursor, self.value.len());
+    }
+
+    pub fn history_back(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            Some(index) => index.saturating_sub(1),
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            },
+        };
+        self.set_value(&self.history[index].clone());
+        self.history_index = Some(index);
+    }
+
+    pub fn history_forward(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.set_value(&self.history[index + 1].clone());
+            self.history_index = Some(index + 1);
+        } else {
+            self.set_value(&self.draft.clone());
+            self.history_index = None;
+        }
+    }
+
+    // Returns false for the keys the input does not use, Enter and Esc are left to the caller
+    pub fn key(&mut self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.home(),
+            KeyCode::Char('e') if ctrl => self.end(),
+            KeyCode::Char('b') if alt => self.word_left(),
+            KeyCode::Char('f') if alt => self.word_right(),
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char('u') if ctrl => self.delete_to_start(),
+            KeyCode::Char('k') if ctrl => self.delete_to_end(),
+            KeyCode::Char(ch) if !ctrl && !alt => self.insert(ch),
+            KeyCode::Left if ctrl || alt => self.word_left(),
+            KeyCode::Right if ctrl || alt => self.word_right(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            KeyCode::Backspace if ctrl || alt => self.delete_word(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Up => self.history_back(),
+            KeyCode::Down => self.history_forward(),
+            _ => return false,
+        }
+        true
+    }
+
+    // The part of the value that fits in the width and the column of the cursor in it
+    pub fn view(&self, max: usize) -> (String, usize) {
+        let (before, after) = (self.value[..self.cursor].to_string(), self.value[self.cursor..].to_string());
+
+        // One column is kept for the cursor
+        let column = text::width(&before);
+        if column < max {
+            return (text::take(&(before + after.as_str()), max), column);
+        }
+        let before = text::take_last(&before, max.saturating_sub(1));
+        let column = text::width(&before);
+        (before + text::take(&after, max - column).as_str(), column)
+    }
+}
@@ -0,0 +1,384 @@
+use crate::window::{palette::Command, text};
+use config::Config;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Back,
+    Open,
+    OpenWith,
+    View,
+    Pipe,
+    TogglePreview,
+    ShowPath,
+    Sort,
+    Filter,
+    Help,
+    Command,
+    Breadcrumbs,
+    Mark,
+    ScrollLeft,
+    ScrollRight,
+    Shell,
+    Suspend,
+}
+
+impl Action {
+    pub const CATEGORIES: [&'static str; 4] = ["Navigation", "Files", "Display", "General"];
+
+    pub const ALL: [Action; 23] = [
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Enter,
+        Action::Back,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::Breadcrumbs,
+        Action::Open,
+        Action::OpenWith,
+        Action::View,
+        Action::Pipe,
+        Action::Mark,
+        Action::Shell,
+        Action::TogglePreview,
+        Action::ShowPath,
+        Action::Sort,
+        Action::Filter,
+        Action::Help,
+        Action::Command,
+        Action::Suspend,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Enter => "enter",
+            Action::Back => "back",
+            Action::Open => "open",
+            Action::OpenWith => "open-with",
+            Action::View => "view",
+            Action::Pipe => "pipe",
+            Action::TogglePreview => "toggle-preview",
+            Action::ShowPath => "show-path",
+            Action::Sort => "sort",
+            Action::Filter => "filter",
+            Action::Help => "help",
+            Action::Command => "command",
+            Action::Breadcrumbs => "breadcrumbs",
+            Action::Mark => "mark",
+            Action::ScrollLeft => "scroll-left",
+            Action::ScrollRight => "scroll-right",
+            Action::Shell => "shell",
+            Action::Suspend => "suspend",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit content-7z",
+            Action::Up => "Move the cursor up",
+            Action::Down => "Move the cursor down",
+            Action::Left => "Move the cursor left",
+            Action::Right => "Move the cursor right",
+            Action::Enter => "Enter the folder or compressed file",
+            Action::Back => "Go back to the parent folder",
+            Action::Open => "Open the file",
+            Action::OpenWith => "Choose how to open the file",
+            Action::View => "Show the file in the built-in viewer",
+            Action::Pipe => "Pipe the file into a shell command",
+            Action::TogglePreview => "Show or hide the preview pane",
+            Action::ShowPath => "Show the current path",
+            Action::Sort => "Sort by name, size, date or as listed",
+            Action::Filter => "Show only the names that contain a text",
+            Action::Help => "Show this help",
+            Action::Command => "Run an action by name",
+            Action::Breadcrumbs => "Jump to a folder of the path",
+            Action::Mark => "Mark or unmark the entry",
+            Action::ScrollLeft => "Scroll long names to the left",
+            Action::ScrollRight => "Scroll long names to the right",
+            Action::Shell => "Open a shell in the extracted folder",
+            Action::Suspend => "Suspend content-7z, 'fg' brings it back",
+        }
+    }
+
+    pub fn category(&self) -> &'static str {
+        match self {
+            Action::Up | Action::Down | Action::Left | Action::Right | Action::Enter | Action::Back | Action::Breadcrumbs
+                | Action::ScrollLeft | Action::ScrollRight => "Navigation",
+            Action::Open | Action::OpenWith | Action::View | Action::Pipe | Action::Mark | Action::Shell => "Files",
+            Action::TogglePreview | Action::ShowPath | Action::Sort | Action::Filter => "Display",
+            Action::Quit | Action::Help | Action::Command | Action::Suspend => "General",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        let name = name.replace('_', "-");
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of the character: 'O' and not shift + 'o'
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+
+        'modifiers:
+        loop {
+            for (prefix, modifier) in [
+                ("ctrl", KeyModifiers::CONTROL),
+                ("alt", KeyModifiers::ALT),
+                ("shift", KeyModifiers::SHIFT),
+            ] {
+                for separator in ["-", "+"] {
+                    let full = prefix.to_string() + separator;
+                    if rest.len() > full.len() && rest.get(..full.len()).is_some_and(|start| start.eq_ignore_ascii_case(&full)) {
+                        modifiers |= modifier;
+                        rest = &rest[full.len()..];
+                        continue 'modifiers;
+                    }
+                }
+            }
+            break;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            // "shift-x" is 'X', what shift does to other characters depends on the keyboard
+            (Some(ch), None) if modifiers.contains(KeyModifiers::SHIFT) => match ch.is_alphabetic() {
+                true => KeyCode::Char(ch.to_uppercase().next().unwrap_or(ch)),
+                false => return None,
+            },
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" | "return" | "cr" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" | "bs" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|number| number.parse::<u8>().ok()) {
+                    Some(number) if (1..=12).contains(&number) => KeyCode::F(number),
+                    _ => return None,
+                },
+            },
+        };
+
+        Some(Self::new(code, modifiers))
+    }
+
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name += "ctrl-";
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name += "alt-";
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            name += "shift-";
+        }
+
+        name += match self.code {
+            KeyCode::Char(' ') => String::from("space"),
+            KeyCode::Char(ch) => ch.to_string(),
+            KeyCode::Enter => String::from("enter"),
+            KeyCode::Esc => String::from("esc"),
+            KeyCode::Backspace => String::from("backspace"),
+            KeyCode::Tab => String::from("tab"),
+            KeyCode::BackTab => String::from("backtab"),
+            KeyCode::Up => String::from("up"),
+            KeyCode::Down => String::from("down"),
+            KeyCode::Left => String::from("left"),
+            KeyCode::Right => String::from("right"),
+            KeyCode::Home => String::from("home"),
+            KeyCode::End => String::from("end"),
+            KeyCode::PageUp => String::from("pageup"),
+            KeyCode::PageDown => String::from("pagedown"),
+            KeyCode::Delete => String::from("delete"),
+            KeyCode::Insert => String::from("insert"),
+            KeyCode::F(number) => format!("f{}", number),
+            _ => String::from("?"),
+        }.as_str();
+        name
+    }
+}
+
+pub struct Keymap {
+    pub bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    pub fn empty() -> Self {
+        Self { bindings: vec![] }
+    }
+
+    pub fn default_preset() -> Self {
+        let mut keymap = Self::empty();
+        for (key, action) in [
+            ("esc", Action::Quit),
+            ("q", Action::Quit),
+            ("up", Action::Up),
+            ("down", Action::Down),
+            ("left", Action::Left),
+            ("right", Action::Right),
+            ("enter", Action::Enter),
+            ("backspace", Action::Back),
+            ("shift-left", Action::ScrollLeft),
+            ("<", Action::ScrollLeft),
+            ("shift-right", Action::ScrollRight),
+            (">", Action::ScrollRight),
+            ("tab", Action::Breadcrumbs),
+            ("o", Action::Open),
+            ("O", Action::OpenWith),
+            ("v", Action::View),
+            ("|", Action::Pipe),
+            ("space", Action::Mark),
+            ("!", Action::Shell),
+            ("i", Action::TogglePreview),
+            ("p", Action::ShowPath),
+            ("s", Action::Sort),
+            ("/", Action::Filter),
+            ("?", Action::Help),
+            (":", Action::Command),
+            ("ctrl-z", Action::Suspend),
+        ] {
+            keymap.bind(key, action);
+        }
+        keymap
+    }
+
+    pub fn vim_preset() -> Self {
+        let mut keymap = Self::default_preset();
+        for (key, action) in [
+            ("k", Action::Up),
+            ("j", Action::Down),
+            ("h", Action::Back),
+            ("l", Action::Enter),
+        ] {
+            keymap.bind(key, action);
+        }
+        keymap
+    }
+
+    pub fn from(config: &Config) -> Self {
+        let mut keymap = match config.get_string("keys.preset") {
+            Ok(preset) if preset == "vim" => Self::vim_preset(),
+            _ => Self::default_preset(),
+        };
+
+        let Ok(table) = config.get_table("keys") else {
+            return keymap;
+        };
+
+        for (name, value) in table {
+            let Some(action) = Action::from_name(&name) else {
+                continue;
+            };
+
+            let keys = match value.clone().into_array() {
+                Ok(list) => list.into_iter().filter_map(|key| key.into_string().ok()).collect(),
+                Err(_) => value.into_string().into_iter().collect::<Vec<String>>(),
+            };
+
+            // Configured keys replace the ones of the preset
+            keymap.bindings.retain(|(_, bound)| *bound != action);
+            for key in keys {
+                keymap.bind(&key, action);
+            }
+        }
+        keymap
+    }
+
+    pub fn bind(&mut self, key: &str, action: Action) {
+        if let Some(binding) = KeyBinding::parse(key) {
+            self.bindings.retain(|(bound, _)| *bound != binding);
+            self.bindings.push((binding, action));
+        }
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        let binding = KeyBinding::new(event.code, event.modifiers);
+        self.bindings.iter()
+            .find(|(bound, _)| *bound == binding)
+            .map(|(_, action)| *action)
+    }
+
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings.iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(binding, _)| binding.name())
+            .collect()
+    }
+
+    pub fn keys_for_command(&self, command: Command) -> Vec<String> {
+        match command {
+            Command::Action(action) => self.keys_for(action),
+            _ => vec![],
+        }
+    }
+
+    pub fn help_lines(&self) -> Vec<String> {
+        let rows: Vec<(String, &Action)> = Action::ALL.iter()
+            .map(|action| (self.keys_for(*action).join(", "), action))
+            .collect();
+        let keys_width = rows.iter().map(|(keys, _)| text::width(keys)).max().unwrap_or(0);
+
+        let mut lines = vec![];
+        for category in Action::CATEGORIES {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", category));
+
+            for (keys, action) in rows.iter().filter(|(_, action)| action.category() == category) {
+                let keys = if keys.is_empty() { "-" } else { keys.as_str() };
+                lines.push(format!(" {}  {}", text::pad(keys, keys_width), action.description()));
+            }
+        }
+        lines
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::default_preset()
+    }
+}
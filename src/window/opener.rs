@@ -0,0 +1,146 @@
+use glob::{MatchOptions, Pattern};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+pub enum OpenerPattern {
+    Glob(Pattern),
+    Extension(String),
+    Mime(String),
+}
+
+pub struct Opener {
+    pub pattern: String,
+    pub command: String,
+    pub kind: OpenerPattern,
+}
+
+impl Opener {
+    // "*.tar.*" is a glob, "image/*" a MIME type and "pdf" an extension
+    pub fn new(pattern: &str, command: &str) -> Self {
+        let kind = if pattern.contains('/') {
+            OpenerPattern::Mime(pattern.to_lowercase())
+        } else if pattern.contains(['*', '?', '[']) {
+            match Pattern::new(pattern) {
+                Ok(glob) => OpenerPattern::Glob(glob),
+                Err(_) => OpenerPattern::Extension(pattern.to_lowercase()),
+            }
+        } else {
+            OpenerPattern::Extension(pattern.trim_start_matches('.').to_lowercase())
+        };
+
+        Self {
+            pattern: String::from(pattern),
+            command: String::from(command),
+            kind,
+        }
+    }
+
+    // Name based rules win over MIME ones, and exact MIME types over wildcards
+    pub fn priority(&self) -> u8 {
+        match &self.kind {
+            OpenerPattern::Glob(_) | OpenerPattern::Extension(_) => 0,
+            OpenerPattern::Mime(mime) if mime.ends_with("/*") => 2,
+            OpenerPattern::Mime(_) => 1,
+        }
+    }
+
+    pub fn matches_name(&self, name: &str) -> bool {
+        match &self.kind {
+            // The config keys arrive lowercased, so globs ignore the case as extensions do
+            OpenerPattern::Glob(glob) => glob.matches_with(name, MatchOptions {
+                case_sensitive: false,
+                ..MatchOptions::new()
+            }),
+            OpenerPattern::Extension(extension) => match name.rsplit_once('.') {
+                Some((_, name_extension)) => name_extension.to_lowercase() == *extension,
+                None => false,
+            },
+            OpenerPattern::Mime(_) => false,
+        }
+    }
+
+    pub fn matches(&self, name: &str, mime: &str) -> bool {
+        match &self.kind {
+            OpenerPattern::Mime(pattern) => match pattern.strip_suffix('*') {
+                Some(prefix) => mime.starts_with(prefix),
+                None => mime == pattern,
+            },
+            _ => self.matches_name(name),
+        }
+    }
+
+    pub fn command_line(&self, file: &Path) -> String {
+        command_line(&self.command, file)
+    }
+}
+
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+// Fills {}, {path}, {name} and {dir} with the extracted file, or appends it
+pub fn command_line(command: &str, file: &Path) -> String {
+    let path = quote(file.to_str().unwrap_or_default());
+    let name = quote(file.file_name().and_then(|name| name.to_str()).unwrap_or_default());
+    let dir = quote(file.parent().and_then(|dir| dir.to_str()).unwrap_or_default());
+
+    if !["{}", "{path}", "{name}", "{dir}"].iter().any(|holder| command.contains(holder)) {
+        return format!("{} {}", command, path);
+    }
+
+    // One pass over the command, so text already filled in is never scanned again
+    let mut line = String::new();
+    let mut rest = command;
+    while let Some(start) = rest.find('{') {
+        line += &rest[..start];
+        rest = &rest[start..];
+
+        let holder = [("{}", &path), ("{path}", &path), ("{name}", &name), ("{dir}", &dir)]
+            .into_iter()
+            .find(|(holder, _)| rest.starts_with(holder));
+        match holder {
+            Some((holder, value)) => {
+                line += value;
+                rest = &rest[holder.len()..];
+            }
+            None => {
+                line.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    line + rest
+}
+
+pub fn shell(command_line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", command_line]);
+    command
+}
+
+pub fn mime_type(file: &Path) -> String {
+    let output = Command::new("file")
+        .args(["--mime-type", "-b"])
+        .arg(file)
+        .stderr(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+pub fn is_text_mime(mime: &str) -> bool {
+    mime.is_empty()
+        || mime.starts_with("text/")
+        || mime == "inode/x-empty"
+        || ["json", "xml", "javascript", "x-sh", "x-shellscript", "toml", "yaml"].iter().any(|kind| mime.ends_with(kind))
+}
+
+pub fn matching<'a>(openers: &'a [Opener], file: &Path, mime: &str) -> Vec<&'a Opener> {
+    let name = file.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    openers.iter().filter(|opener| opener.matches(name, mime)).collect()
+}
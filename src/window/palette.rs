@@ -0,0 +1,181 @@
+use crate::window::keymap::Action;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Command {
+    Action(Action),
+    Extract,
+    Test,
+    Cd,
+}
+
+impl Command {
+    pub fn all() -> Vec<Command> {
+        let mut commands: Vec<Command> = Action::ALL.iter()
+            .filter(|action| **action != Action::Command)
+            .map(|action| Command::Action(*action))
+            .collect();
+        commands.extend([Command::Extract, Command::Test, Command::Cd]);
+        commands
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Action(action) => action.name(),
+            Command::Extract => "extract",
+            Command::Test => "test",
+            Command::Cd => "cd",
+        }
+    }
+
+    pub fn usage(&self) -> &'static str {
+        match self {
+            Command::Extract => "extract [dir]",
+            Command::Cd => "cd <path>",
+            _ => self.name(),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Command::Action(action) => action.description(),
+            Command::Extract => "Extract the selected entry, by default here",
+            Command::Test => "Test the integrity of the archive",
+            Command::Cd => "Go to a folder of the archive",
+        }
+    }
+}
+
+pub struct Palette {
+    pub visible: bool,
+    pub value: String,
+    pub selected: usize,
+    pub history: Vec<String>,
+    pub history_index: Option<usize>,
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            value: String::new(),
+            selected: 0,
+            history: vec![],
+            history_index: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.visible = true;
+        self.value.clear();
+        self.selected = 0;
+        self.history_index = None;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+
+    // The first word picks the command, the rest are its arguments
+    pub fn split(&self) -> (&str, &str) {
+        let value = self.value.trim_start();
+        match value.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (value, ""),
+        }
+    }
+
+    pub fn matches(&self) -> Vec<Command> {
+        let (name, _) = self.split();
+        let mut found: Vec<(usize, Command)> = Command::all().into_iter()
+            .filter_map(|command| fuzzy_score(name, command.name()).map(|score| (score, command)))
+            .collect();
+        found.sort_by(|(score, command), (other_score, other)| {
+            score.cmp(other_score).then(command.name().cmp(other.name()))
+        });
+        found.into_iter().map(|(_, command)| command).collect()
+    }
+
+    // The highlighted match, an exact name is already the first one
+    pub fn chosen(&self) -> Option<Command> {
+        self.matches().get(self.selected).copied()
+    }
+
+    pub fn select_next(&mut self, forward: bool) {
+        let count = self.matches().len();
+        if count == 0 {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1) % count
+        } else {
+            (self.selected + count - 1) % count
+        };
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.value.push(ch);
+        self.selected = 0;
+        self.history_index = None;
+    }
+
+    pub fn pop(&mut self) {
+        self.value.pop();
+        self.selected = 0;
+        self.history_index = None;
+    }
+
+    pub fn history_back(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            Some(index) => index.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.history_index = Some(index);
+        self.value = self.history[index].clone();
+        self.selected = 0;
+    }
+
+    pub fn history_forward(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.value = self.history[index + 1].clone();
+        } else {
+            self.history_index = None;
+            self.value.clear();
+        }
+        self.selected = 0;
+    }
+
+    pub fn remember(&mut self, line: &str) {
+        let line = line.trim();
+        if !line.is_empty() && self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(String::from(line));
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Lower is better, None when the pattern is not a subsequence of the text
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+
+    for (index, ch) in pattern.to_lowercase().chars().enumerate() {
+        let found = text[position..].iter().position(|other| *other == ch)?;
+        // Skipped characters cost, the ones before the first match cost less
+        score += if index == 0 { found } else { found * 2 };
+        position += found + 1;
+    }
+    Some(score)
+}
@@ -0,0 +1,246 @@
+use crate::{
+    files::{entry::Entry, folder::Folder},
+    window::text,
+    zip_manager::reader::{BackgroundRead, MemberReader},
+};
+use std::time::{Duration, Instant};
+
+pub const PREVIEW_DELAY: Duration = Duration::from_millis(150);
+
+pub struct Preview {
+    pub member: String,
+    pub bytes: Vec<u8>,
+    pub binary: bool,
+    pub truncated: bool,
+    pub listing: Option<Vec<String>>,
+    pub error: Option<String>,
+    pub loading: bool,
+}
+
+pub struct PreviewState {
+    pub enabled: bool,
+    pub current: Option<Preview>,
+    pub wanted: Option<(String, Instant)>,
+    pub pending: Option<(BackgroundRead, usize)>,
+}
+
+impl PreviewState {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            current: None,
+            wanted: None,
+            pending: None,
+        }
+    }
+
+    // Extracting the start of a member can take long in solid archives, it goes on while the list is used
+    pub fn load(&mut self, archive: &str, member: &str, size: usize) {
+        self.wanted = None;
+        match MemberReader::open(archive, &member[1..]) {
+            Ok(reader) => {
+                // One byte more tells a member of exactly "size" bytes from a longer one
+                self.pending = Some((reader.read_in_background(size + 1), size));
+                self.current = Some(Preview::loading(member));
+            },
+            Err(err) => {
+                self.pending = None;
+                self.current = Some(Preview::error(member, err));
+            },
+        }
+    }
+
+    pub fn show(&mut self, preview: Preview) {
+        self.wanted = None;
+        self.pending = None;
+        self.current = Some(preview);
+    }
+
+    // Returns true when the member being loaded arrived
+    pub fn update(&mut self) -> bool {
+        let Some(bytes) = self.pending.as_ref().and_then(|(read, _)| read.take()) else {
+            return false;
+        };
+        let (_, size) = self.pending.take().unwrap();
+        let member = String::from(self.loaded());
+        self.current = Some(Preview::text(&member, bytes, size));
+        true
+    }
+
+    // The extraction of a member that is not selected anymore is stopped
+    pub fn forget_unless(&mut self, member: &str) {
+        if self.pending.is_some() && self.loaded() != member {
+            self.pending = None;
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.wanted = None;
+        self.pending = None;
+    }
+
+    pub fn loaded(&self) -> &str {
+        match &self.current {
+            Some(preview) => preview.member.as_str(),
+            None => "",
+        }
+    }
+
+    // Returns true once the member has been selected long enough to be worth loading
+    pub fn want(&mut self, member: &str) -> bool {
+        if self.loaded() == member {
+            self.wanted = None;
+            return false;
+        }
+
+        match &self.wanted {
+            Some((wanted, since)) if wanted == member => since.elapsed() >= PREVIEW_DELAY,
+            _ => {
+                self.wanted = Some((String::from(member), Instant::now()));
+                false
+            },
+        }
+    }
+}
+
+impl Default for PreviewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Preview {
+    pub fn empty() -> Self {
+        Self {
+            member: String::new(),
+            bytes: vec![],
+            binary: false,
+            truncated: false,
+            listing: None,
+            error: None,
+            loading: false,
+        }
+    }
+
+    pub fn loading(member: &str) -> Self {
+        let mut preview = Self::empty();
+        preview.member = String::from(member);
+        preview.loading = true;
+        preview
+    }
+
+    pub fn error(member: &str, err: String) -> Self {
+        let mut preview = Self::empty();
+        preview.member = String::from(member);
+        preview.error = Some(err);
+        preview
+    }
+
+    // The first "size" bytes, the rest only shows as "..."
+    pub fn text(member: &str, mut bytes: Vec<u8>, size: usize) -> Self {
+        let mut preview = Self::empty();
+        preview.member = String::from(member);
+        preview.truncated = bytes.len() > size;
+        bytes.truncate(size);
+        preview.binary = is_binary(&bytes);
+        preview.bytes = bytes;
+        preview
+    }
+
+    pub fn folder(member: &str, folder: &Folder) -> Self {
+        let mut preview = Self::empty();
+        preview.member = String::from(member);
+        preview.listing = Some(folder.content.iter().map(|entry| match entry {
+            Entry::File(file_name) => file_name.clone(),
+            Entry::Folder(folder) => folder.name.clone() + "/",
+        }).collect());
+        preview
+    }
+
+    pub fn lines(&self, width: usize) -> Vec<String> {
+        if width == 0 {
+            return vec![];
+        }
+
+        if let Some(err) = &self.error {
+            return wrap(err, width);
+        }
+
+        if self.loading {
+            return vec![text::truncate("Loading…", width)];
+        }
+
+        if let Some(listing) = &self.listing {
+            return listing.iter().map(|line| text::truncate(line, width)).collect();
+        }
+
+        let mut lines = if self.binary {
+            hex_lines(&self.bytes, 0, width)
+        } else {
+            let text = String::from_utf8_lossy(&self.bytes);
+            text.lines().flat_map(|line| wrap(line, width)).collect()
+        };
+
+        if self.truncated {
+            lines.push(String::from("..."));
+        }
+        lines
+    }
+}
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return true;
+    }
+
+    // The content may be cut in the middle of a character
+    if let Err(err) = std::str::from_utf8(bytes) {
+        if err.error_len().is_some() {
+            return true;
+        }
+    }
+
+    let controls = bytes.iter()
+        .filter(|byte| byte.is_ascii_control() && !matches!(byte, b'\n' | b'\r' | b'\t' | 0x0c))
+        .count();
+    controls * 10 > bytes.len()
+}
+
+pub fn wrap(line: &str, width: usize) -> Vec<String> {
+    let line: String = line.replace('\t', "    ")
+        .chars()
+        .filter(|character| !character.is_control())
+        .collect();
+
+    text::wrap(&line, width)
+}
+
+// Bytes per row that fit in the width: "offset  hex... ascii"
+pub fn hex_row_size(width: usize) -> usize {
+    let mut size = 16;
+    while size > 1 && 10 + size * 3 + size > width {
+        size /= 2;
+    }
+    size
+}
+
+pub fn hex_lines(bytes: &[u8], offset: usize, width: usize) -> Vec<String> {
+    let size = hex_row_size(width);
+
+    bytes.chunks(size).enumerate().map(|(index, chunk)| {
+        let mut line = format!("{:08x}  ", offset + index * size);
+        for byte in chunk {
+            line += format!("{:02x} ", byte).as_str();
+        }
+        line += "   ".repeat(size - chunk.len()).as_str();
+        for byte in chunk {
+            line.push(if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' });
+        }
+        line.chars().take(width).collect()
+    }).collect()
+}
@@ -0,0 +1,621 @@
+use crate::{
+    files::entry::{human_size, Entry},
+    window::{
+        window::{Window, SortOrder},
+        scheme::{NOCOLOR, REVERSE, WARNING},
+        viewer::{ViewerMode, VIEWER_KEYS, VIEWER_LIMIT},
+        palette::Command as PaletteCommand,
+        breadcrumbs,
+        text,
+    },
+};
+use crossterm::{
+    terminal::{Clear, ClearType}, cursor::MoveTo,
+    QueueableCommand,
+};
+use std::io::Write;
+
+pub fn print_header(win: &Window) {
+    let fill_all_block = "─".repeat(usize::from(win.width) - 2);
+    let mut stdout = &win.screen;
+    let crumbs = win.crumbs();
+
+    stdout.queue(MoveTo(0, 0)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(("┌".to_string() + fill_all_block.as_str() + "┐").as_bytes()).unwrap();
+
+    stdout.queue(MoveTo(0, 1)).unwrap();
+    stdout.queue(Clear(ClearType::CurrentLine)).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all("│".as_bytes()).unwrap();
+
+    let mut used = 0;
+    for (index, crumb) in crumbs.iter().enumerate() {
+        if index > 0 {
+            stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+            stdout.write_all(breadcrumbs::SEPARATOR.as_bytes()).unwrap();
+        }
+
+        stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+        let selected = crumb.depth.is_some() && crumb.depth == win.breadcrumb;
+        if selected {
+            stdout.write_all(REVERSE).unwrap();
+        }
+        stdout.write_all(crumb.text.as_bytes()).unwrap();
+        if selected {
+            stdout.write_all(NOCOLOR).unwrap();
+            stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+        }
+        used = crumb.column + crumb.width();
+    }
+    stdout.write_all(" ".repeat(usize::from(win.width - 2).saturating_sub(used)).as_bytes()).unwrap();
+
+    let loading = text::truncate(&win.loading_label(), usize::from(win.width - 2));
+    if !loading.is_empty() {
+        stdout.queue(MoveTo(win.width - 1 - text::width(&loading) as u16, 1)).unwrap();
+        stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+        stdout.write_all(loading.as_bytes()).unwrap();
+    }
+
+    stdout.queue(MoveTo(win.width - 1, 1)).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all("│".as_bytes()).unwrap();
+
+    stdout.queue(MoveTo(0, 2)).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(("└".to_string() + fill_all_block.as_str() + "┘").as_bytes()).unwrap();
+    stdout.write_all(NOCOLOR).unwrap();
+}
+
+pub fn print_menu(win: &Window) {
+    let width = win.list_width();
+    let fill_all_block = "─".repeat(usize::from(width) - 2);
+    let mut stdout = &win.screen;
+
+    stdout.queue(MoveTo(0, 3)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(("┌".to_string() + fill_all_block.as_str() + "┐").as_bytes()).unwrap();
+
+    for i in 4..win.height - 1 {
+        print_row(win, i);
+    }
+
+    print_status(win);
+}
+
+pub fn print_row(win: &Window, i: u16) {
+    let width = win.list_width();
+    let max_name = usize::from(width) - 2;
+    let mut stdout = &win.screen;
+
+    stdout.queue(MoveTo(0, i)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all("│".as_bytes()).unwrap();
+
+    let index = usize::from(i - 4) + win.scroll_y;
+    let mut used = 0;
+    if let Some(entry) = win.get_current().content.get(index) {
+        let (bullet, bullet_color) = match entry {
+            Entry::File(_) => (&win.scheme.file_bullet, &win.scheme.file_bullet_color),
+            Entry::Folder(_) => (&win.scheme.folder_bullet, &win.scheme.folder_bullet_color),
+        };
+        // Entries that are not extracted for being unsafe get a warning instead of the bullet
+        if win.hazard(entry).is_some() {
+            stdout.write_all(WARNING).unwrap();
+            stdout.write_all(text::pad("!", text::width(bullet)).as_bytes()).unwrap();
+        } else {
+            stdout.write_all(bullet_color.repr.as_bytes()).unwrap();
+            stdout.write_all(bullet.as_bytes()).unwrap();
+        }
+        stdout.write_all(NOCOLOR).unwrap();
+        stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+        stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+
+        let room = win.name_room(entry);
+        let offset = if index == win.selected_index() {
+            win.marquee_offset(room).unwrap_or(usize::from(win.scroll_x))
+        } else {
+            usize::from(win.scroll_x)
+        };
+        let name = text::truncate(&text::skip(Window::entry_name(entry), offset), room);
+
+        let marked = win.is_marked(entry);
+        if marked {
+            stdout.write_all(REVERSE).unwrap();
+        }
+        stdout.write_all(name.as_bytes()).unwrap();
+        if marked {
+            stdout.write_all(NOCOLOR).unwrap();
+            stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+        }
+        used = text::width(bullet) + text::width(&name);
+    }
+    stdout.write_all(" ".repeat(max_name.saturating_sub(used)).as_bytes()).unwrap();
+
+    stdout.queue(MoveTo(width - 1, i)).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all("│".as_bytes()).unwrap();
+    stdout.write_all(NOCOLOR).unwrap();
+}
+
+pub fn status_text(win: &Window) -> String {
+    let content = &win.get_current().content;
+    let mut parts = vec![];
+    if content.is_empty() {
+        parts.push(String::from("0/0"));
+    } else {
+        parts.push(format!("{}/{}", (win.selected_index() + 1).min(content.len()), content.len()));
+    }
+    if win.sort != SortOrder::Archive {
+        parts.push(String::from(win.sort.label()));
+    }
+    if !win.filter.is_empty() {
+        parts.push(format!("filter \"{}\"", win.filter));
+    }
+
+    if let Some(message) = win.get_message() {
+        parts.push(String::from(message));
+    } else if let Some(hazard) = win.get_selected().and_then(|entry| win.hazard(entry)) {
+        parts.push(format!("unsafe, {}: it is not extracted", hazard.describe()));
+    } else {
+        match win.get_selected() {
+            Some(entry @ Entry::File(name)) => {
+                parts.push(human_size(win.entry_size(entry)));
+                if let Some(info) = win.archive_info().get(&win.member_path(name)) {
+                    if !info.date.is_empty() {
+                        parts.push(info.date.clone());
+                    }
+                    if let Some(ratio) = info.ratio() {
+                        parts.push(format!("{}%", ratio));
+                    }
+                }
+            },
+            Some(entry @ Entry::Folder(folder)) => {
+                let count = folder.content.len();
+                parts.push(format!("{} item{}", count, if count == 1 { "" } else { "s" }));
+                parts.push(human_size(win.entry_size(entry)));
+            },
+            None => {},
+        }
+    }
+
+    if !win.marked.is_empty() {
+        let total: u64 = win.marked.iter().map(|(_, size)| size).sum();
+        parts.push(format!("{} marked, {}", win.marked.len(), human_size(total)));
+    }
+    parts.join(" · ")
+}
+
+// The bottom border of the list doubles as a status bar
+pub fn print_status(win: &Window) {
+    let width = usize::from(win.list_width());
+    let mut stdout = &win.screen;
+    let status = text::truncate(&status_text(win), width.saturating_sub(6));
+
+    stdout.queue(MoveTo(0, win.height - 1)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all("└─ ".as_bytes()).unwrap();
+    stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+    stdout.write_all(status.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(" ".as_bytes()).unwrap();
+    stdout.write_all("─".repeat(width.saturating_sub(5 + text::width(&status))).as_bytes()).unwrap();
+    stdout.write_all("┘".as_bytes()).unwrap();
+    stdout.write_all(NOCOLOR).unwrap();
+}
+
+pub fn print_preview(win: &Window) {
+    if win.list_width() == win.width {
+        return;
+    }
+
+    let x = win.list_width();
+    let width = win.width - x;
+    let inner_width = usize::from(width) - 2;
+    let mut stdout = &win.screen;
+
+    let (title, lines) = match &win.preview.current {
+        Some(preview) => {
+            let title = match preview.member.rfind('/') {
+                Some(index) => &preview.member[index + 1..],
+                None => preview.member.as_str(),
+            };
+            (text::truncate(title, inner_width), preview.lines(inner_width))
+        },
+        None => (String::new(), vec![]),
+    };
+
+    stdout.queue(MoveTo(x, 3)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(("┌".to_string() + title.as_str() + "─".repeat(inner_width - text::width(&title)).as_str() + "┐").as_bytes()).unwrap();
+
+    for i in 4..win.height - 1 {
+        stdout.queue(MoveTo(x, i)).unwrap();
+        stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+        stdout.write_all("│".as_bytes()).unwrap();
+        stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+
+        let line = match lines.get(usize::from(i - 4)) {
+            Some(line) => line.as_str(),
+            None => "",
+        };
+        stdout.write_all(line.as_bytes()).unwrap();
+        stdout.write_all(" ".repeat(inner_width.saturating_sub(text::width(line))).as_bytes()).unwrap();
+
+        stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+        stdout.write_all("│".as_bytes()).unwrap();
+    }
+
+    stdout.queue(MoveTo(x, win.height - 1)).unwrap();
+    stdout.write_all(("└".to_string() + "─".repeat(inner_width).as_str() + "┘").as_bytes()).unwrap();
+    stdout.write_all(NOCOLOR).unwrap();
+}
+
+pub fn print_viewer(win: &mut Window) {
+    let mut stdout = &win.screen;
+    let width = usize::from(win.width);
+    let Some(viewer) = win.viewer.as_mut() else {
+        return;
+    };
+
+    let rows = viewer.rows();
+    let mode = match viewer.mode {
+        ViewerMode::Text => "TEXT",
+        ViewerMode::Hex => "HEX",
+    };
+    let position = match viewer.position() {
+        Some(position) => format!("{}%", position),
+        None => format!("{} KB...", viewer.bytes().len() / 1024),
+    };
+    let status = match viewer.is_cut() {
+        true => format!(" {} {} of the first {} ", mode, position, human_size(VIEWER_LIMIT as u64)),
+        false => format!(" {} {} ", mode, position),
+    };
+    let title = text::truncate(&format!(" {}", viewer.title), width.saturating_sub(status.len()));
+
+    stdout.queue(MoveTo(0, 0)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(title.as_bytes()).unwrap();
+    stdout.write_all(" ".repeat(width.saturating_sub(text::width(&title) + status.len())).as_bytes()).unwrap();
+    stdout.write_all(status.as_bytes()).unwrap();
+
+    stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+    for i in 1..win.height - 1 {
+        stdout.queue(MoveTo(0, i)).unwrap();
+        let row = match rows.get(usize::from(i - 1)) {
+            Some(row) => row.as_str(),
+            None => "~",
+        };
+        stdout.write_all(row.as_bytes()).unwrap();
+        stdout.write_all(" ".repeat(width.saturating_sub(text::width(row))).as_bytes()).unwrap();
+    }
+
+    let footer = if let Some(prompt) = &viewer.prompt {
+        format!("/{}", prompt)
+    } else if let Some(message) = &viewer.message {
+        message.clone()
+    } else {
+        String::from("q: back  /: search  n/N: next/previous  Tab: text/hex")
+    };
+    let footer = text::truncate(&footer, width);
+
+    stdout.queue(MoveTo(0, win.height - 1)).unwrap();
+    stdout.write_all(win.scheme.border_color.repr.as_bytes()).unwrap();
+    stdout.write_all(footer.as_bytes()).unwrap();
+    stdout.write_all(" ".repeat(width.saturating_sub(text::width(&footer))).as_bytes()).unwrap();
+    stdout.write_all(NOCOLOR).unwrap();
+
+    match &viewer.prompt {
+        Some(prompt) => stdout.queue(MoveTo(text::width(prompt).min(width - 1) as u16 + 1, win.height - 1)).unwrap(),
+        None => stdout.queue(MoveTo(0, win.height - 1)).unwrap(),
+    };
+}
+
+pub fn show_dialog_raw(win: &mut Window, text: String, helper: Option<&str>) -> (u16, u16) {
+    let mut stdout = &win.screen;
+
+    let limit = usize::from(win.width.saturating_sub(8)).max(1);
+    let mut lines = vec![];
+    let mut max_length = 0;
+    for line in text.split('\n') {
+        for row in text::wrap(line, limit) {
+            max_length = max_length.max(text::width(&row) as u16);
+            lines.push(row);
+        }
+    }
+
+    let mut helper_label = vec![];
+    if let Some(label) = helper {
+        for line in label.split('\n') {
+            for row in text::wrap(line, limit) {
+                max_length = max_length.max(text::width(&row) as u16);
+                helper_label.push(row);
+            }
+        }
+    }
+
+    let helper_label_increment = if helper_label.len() < 2 {
+        0
+    } else {
+        helper_label.len() - 1
+    };
+
+    // Whatever does not fit in the window is left out
+    lines.truncate(usize::from(win.height).saturating_sub(helper_label_increment + 2));
+    let x: u16 = (win.width / 2).saturating_sub((max_length + 2) / 2);
+    let y: u16 = (win.height / 2).saturating_sub((lines.len() + helper_label_increment + 2) as u16 / 2);
+
+    let fill_all_block = "─".repeat(usize::from(max_length));
+    let border = win.scheme.border_color.repr.as_bytes();
+    let text_color = win.scheme.text_color.repr.as_bytes();
+
+    stdout.queue(MoveTo(x, y)).unwrap();
+    stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+    stdout.write_all(border).unwrap();
+    stdout.write_all("┌".as_bytes()).unwrap();
+    stdout.write_all(fill_all_block.as_bytes()).unwrap();
+    stdout.write_all("┐".as_bytes()).unwrap();
+
+    for (index, line) in lines.iter().enumerate() {
+        stdout.queue(MoveTo(x, y + 1 + index as u16)).unwrap();
+        stdout.write_all(border).unwrap();
+        stdout.write_all("│".as_bytes()).unwrap();
+        stdout.write_all(text_color).unwrap();
+        stdout.write_all(line.as_bytes()).unwrap();
+        stdout.write_all(" ".repeat(usize::from(max_length).saturating_sub(text::width(line))).as_bytes()).unwrap();
+        stdout.queue(MoveTo(x + max_length + 1, y + 1 + index as u16)).unwrap();
+        stdout.write_all(border).unwrap();
+        stdout.write_all("│".as_bytes()).unwrap();
+    }
+
+    stdout.queue(MoveTo(x, y + 1 + lines.len() as u16)).unwrap();
+    if helper_label.len() < 2 {
+        stdout.write_all("└".as_bytes()).unwrap();
+        stdout.write_all(fill_all_block.as_bytes()).unwrap();
+        stdout.write_all("┘".as_bytes()).unwrap();
+
+        if helper_label.len() == 1 {
+            stdout.queue(MoveTo(x + max_length / 2 - text::width(&helper_label[0]) as u16 / 2 + 1, y + 1 + lines.len() as u16)).unwrap();
+            stdout.write_all(text_color).unwrap();
+            stdout.write_all(helper_label[0].as_bytes()).unwrap();
+        }
+    } else {
+        stdout.write_all("├".as_bytes()).unwrap();
+        stdout.write_all(fill_all_block.as_bytes()).unwrap();
+        stdout.write_all("┤".as_bytes()).unwrap();
+
+        stdout.queue(MoveTo(x, y + helper_label.len() as u16 + lines.len() as u16)).unwrap();
+        stdout.write_all("└".as_bytes()).unwrap();
+        stdout.write_all(fill_all_block.as_bytes()).unwrap();
+        stdout.write_all("┘".as_bytes()).unwrap();
+
+        for (index, label) in helper_label.iter().enumerate() {
+            if index != 0 && index != helper_label.len() - 1 {
+                stdout.queue(MoveTo(x, y + 1 + lines.len() as u16 + index as u16)).unwrap();
+                stdout.write_all(border).unwrap();
+                stdout.write_all("│".as_bytes()).unwrap();
+                stdout.write_all(" ".repeat(usize::from(max_length)).as_bytes()).unwrap();
+                stdout.write_all("│".as_bytes()).unwrap();
+            }
+
+            stdout.queue(MoveTo(x + max_length / 2 - text::width(label) as u16 / 2 + 1, y + 1 + lines.len() as u16 + index as u16)).unwrap();
+            stdout.write_all(text_color).unwrap();
+            stdout.write_all(label.as_bytes()).unwrap();
+        }
+    }
+    stdout.write_all(NOCOLOR).unwrap();
+
+    win.on_dialog = true;
+
+    if helper_label.is_empty() {
+        stdout.queue(MoveTo(win.width / 2, win.height / 2)).unwrap();
+    } else {
+        stdout.queue(MoveTo(x + max_length / 2 + 1, y + (lines.len() + helper_label.len() / 2) as u16 + 1)).unwrap();
+    }
+
+    (x, y + lines.len() as u16)
+}
+
+pub fn show_dialog(win: &mut Window, text: String) {
+    show_dialog_raw(win, text, None);
+} 
+
+pub fn print_input(win: &mut Window) {
+    let Some(input) = &win.input else {
+        return;
+    };
+
+    // Long values scroll to keep the cursor in the dialog
+    let max = usize::from(win.width).saturating_sub(12);
+    let (value, column) = input.view(max);
+    let text = format!("{}\n> {}", input.quest, value);
+
+    let (x, y) = show_dialog_raw(win, text, Some("\nEnter to accept, Esc to cancel\n"));
+    let mut stdout = &win.screen;
+    stdout.queue(MoveTo(x + 3 + column as u16, y)).unwrap();
+    win.cursor.need_update = false;
+}
+
+pub fn print_select(win: &mut Window) {
+    let rows = usize::from(win.height).saturating_sub(12).max(1);
+    let limit = usize::from(win.width).saturating_sub(8);
+    let Some(select) = win.select.as_mut() else {
+        return;
+    };
+    select.scroll_to(rows);
+
+    let matches = select.matches();
+    let mut head = vec![select.quest.clone()];
+    if let Some(filter) = &select.filter {
+        head.push(format!("> {}", filter));
+    }
+    let helper = "\nEnter to choose, Esc to cancel\n";
+
+    // Every row is padded to the widest so the selected one can be highlighted whole
+    let items: Vec<String> = matches.iter()
+        .skip(select.scroll)
+        .take(rows)
+        .map(|index| text::truncate(&format!(" {}", select.items[*index]), limit))
+        .collect();
+    let widest = head.iter()
+        .flat_map(|line| text::wrap(line, limit))
+        .chain(items.iter().cloned())
+        .map(|line| text::width(&line))
+        .chain(helper.split('\n').map(text::width))
+        .max()
+        .unwrap_or(0)
+        .min(limit);
+    let items: Vec<String> = items.iter().map(|item| text::pad(item, widest)).collect();
+    let selected = select.selected.checked_sub(select.scroll);
+    let filter_width = select.filter.as_ref().map(|filter| text::width(filter));
+
+    let mut lines = head.clone();
+    if items.is_empty() {
+        lines.push(text::pad(" No matches", widest));
+    }
+    lines.extend(items.iter().cloned());
+
+    let (x, y) = show_dialog_raw(win, lines.join("\n"), Some(helper));
+    let mut stdout = &win.screen;
+    // In a window too small for the whole dialog, the rows left out cannot be highlighted
+    let first = (y + 1).saturating_sub(items.len().max(1) as u16);
+    if let Some(row) = selected.filter(|row| *row < items.len() && first + (*row as u16) <= y) {
+        stdout.queue(MoveTo(x + 1, first + row as u16)).unwrap();
+        stdout.write_all(win.scheme.background_color.repr.as_bytes()).unwrap();
+        stdout.write_all(win.scheme.text_color.repr.as_bytes()).unwrap();
+        stdout.write_all(REVERSE).unwrap();
+        stdout.write_all(items[row].as_bytes()).unwrap();
+        stdout.write_all(NOCOLOR).unwrap();
+    }
+
+    // The cursor stays where the filter is typed
+    if let Some(width) = filter_width {
+        stdout.queue(MoveTo(x + 3 + width as u16, first.saturating_sub(1))).unwrap();
+    }
+    win.cursor.need_update = false;
+}
+
+pub fn print_task(win: &mut Window) {
+    let Some(task) = &win.task else {
+        return;
+    };
+
+    // Every line has the same width so the dialog does not change size while it runs
+    let width = usize::from(win.width).saturating_sub(12).min(50);
+    let bar = width.saturating_sub(5);
+    let percent = usize::from(task.progress.percent);
+    let filled = bar * percent / 100;
+
+    let mut details = vec![];
+    if let Some(files) = task.progress.files {
+        if task.total_files > 0 {
+            details.push(format!("{}/{} files", files.min(task.total_files), task.total_files));
+        }
+    }
+    if task.total_bytes > 0 {
+        details.push(format!("{} of {}", human_size(task.bytes_done()), human_size(task.total_bytes)));
+    }
+    if let Some(eta) = task.eta() {
+        details.push(format!("{}:{:02} left", eta.as_secs() / 60, eta.as_secs() % 60));
+    }
+
+    let lines = [
+        text::pad(&text::truncate(&task.title, width), width),
+        text::pad(&text::truncate_left(&task.progress.current, width), width),
+        format!("{}{} {:>3}%", "█".repeat(filled), "░".repeat(bar - filled), percent),
+        text::pad(&text::truncate(&details.join(" · "), width), width),
+    ];
+    show_dialog_raw(win, lines.join("\n"), Some("\nEsc to cancel\n"));
+    win.cursor.need_update = false;
+}
+
+pub fn help_lines(win: &Window) -> Vec<String> {
+    let mut lines = win.keymap.help_lines();
+    lines.push(String::new());
+    lines.push(String::from("[Viewer]"));
+
+    let keys_width = VIEWER_KEYS.iter().map(|(keys, _)| text::width(keys)).max().unwrap_or(0);
+    for (keys, description) in VIEWER_KEYS {
+        lines.push(format!(" {}  {}", text::pad(keys, keys_width), description));
+    }
+    lines
+}
+
+pub fn print_help(win: &mut Window) {
+    let Some(scroll) = win.help else {
+        return;
+    };
+
+    let lines = help_lines(win);
+    let visible = usize::from(win.height).saturating_sub(10).max(1);
+    let scroll = scroll.min(lines.len().saturating_sub(visible));
+    win.help = Some(scroll);
+
+    let text = lines[scroll..lines.len().min(scroll + visible)].join("\n");
+    let helper = if lines.len() > visible {
+        format!("\nUp/Down to scroll ({}/{}), Esc to close\n", scroll + 1, lines.len() - visible + 1)
+    } else {
+        String::from("\nEsc to close\n")
+    };
+
+    close_dialog(win);
+    show_dialog_raw(win, text, Some(helper.as_str()));
+}
+
+pub fn print_palette(win: &mut Window) {
+    let visible = usize::from(win.height).saturating_sub(12).clamp(1, 10);
+    let max = usize::from(win.width).saturating_sub(6);
+    let commands = PaletteCommand::all();
+    let usage_width = commands.iter().map(|command| text::width(command.usage())).max().unwrap_or(0);
+
+    let rows: Vec<(String, String)> = commands.iter().map(|command| {
+        (command.usage().to_string(), win.keymap.keys_for_command(*command).join(", "))
+    }).collect();
+    let keys_width = rows.iter().map(|(_, keys)| text::width(keys)).max().unwrap_or(0);
+    let width = commands.iter()
+        .map(|command| 6 + usage_width + keys_width + text::width(command.description()))
+        .max()
+        .unwrap_or(0)
+        .min(max);
+
+    let value_max = width.saturating_sub(2);
+    let value = text::take_last(&win.palette.value, value_max);
+
+    let matches = win.palette.matches();
+    let first = (win.palette.selected + 1).saturating_sub(visible);
+    let mut lines = vec![text::pad(&format!(":{}", value), width), String::new()];
+    for (index, command) in matches.iter().enumerate().skip(first).take(visible) {
+        let marker = if index == win.palette.selected { '>' } else { ' ' };
+        let keys = win.keymap.keys_for_command(*command).join(", ");
+        let line = format!("{} {}  {}  {}", marker, text::pad(command.usage(), usage_width), text::pad(&keys, keys_width), command.description());
+        lines.push(text::truncate(&line, width));
+    }
+    if matches.is_empty() {
+        lines.push(String::from("  No matching command"));
+    }
+    while lines.len() < visible + 2 {
+        lines.push(String::new());
+    }
+
+    let line_count = lines.len() as u16;
+    close_dialog(win);
+    let (x, bottom) = show_dialog_raw(win, lines.join("\n"), Some("\nTab to pick, Up/Down for history, Enter to run\n"));
+    let mut stdout = &win.screen;
+    stdout.queue(MoveTo(x + 2 + text::width(&value) as u16, bottom + 1 - line_count)).unwrap();
+    win.cursor.need_update = false;
+}
+
+pub fn close_dialog(win: &mut Window) {
+    print_header(win);
+    print_menu(win);
+    print_preview(win);
+
+    win.cursor.need_update = true;
+    win.on_dialog = false;
+}
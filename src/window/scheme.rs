@@ -1,3 +1,7 @@
+use crate::{
+    window::opener::Opener,
+    zip_manager::{conflict::Policy, safety::LinkPolicy},
+};
 use config::Config;
 use std::env;
 use which::which;
@@ -16,6 +20,8 @@ pub struct Color {
 }
 
 pub const NOCOLOR: &[u8] = &[27, 91, 48, 109];
+pub const REVERSE: &[u8] = &[27, 91, 55, 109];
+pub const WARNING: &[u8] = &[27, 91, 51, 49, 109];
 
 impl Color {
     pub fn new(r: u8, g: u8, b: u8, color_type: ColorType) -> Self {
@@ -32,7 +38,7 @@ impl Color {
 
     pub fn from(colors: Vec<u8>, color_type: ColorType) -> Self {
         let mut color = Self {
-            r: if colors.len() > 0 {
+            r: if !colors.is_empty() {
                 colors[0]
             } else {
                 0
@@ -89,7 +95,22 @@ pub struct Scheme {
     pub multi_choice_dialog_helper: String,
 
     pub editor: String,
-    pub always_overwrite: bool,
+    pub overwrite: Policy,
+    pub links: LinkPolicy,
+    pub mouse: bool,
+    pub marquee: bool,
+    pub keep_files: bool,
+    pub cache: bool,
+    // Bytes
+    pub cache_size: u64,
+    pub preview_size: usize,
+    pub openers: Vec<Opener>,
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Scheme {
@@ -108,7 +129,15 @@ impl Scheme {
             multi_choice_dialog_helper: String::from("\ny(es) / n(o)\n"),
 
             editor: String::new(),
-            always_overwrite: false,
+            overwrite: Policy::Ask,
+            links: LinkPolicy::FollowWithinRoot,
+            mouse: true,
+            marquee: false,
+            keep_files: false,
+            cache: false,
+            cache_size: 1024 * 1024 * 1024,
+            preview_size: 16 * 1024,
+            openers: vec![],
         }
     }
 
@@ -167,20 +196,61 @@ impl Scheme {
             } else {
                 let identify_editor = vec!["nvim", "vim", "emacs", "nano", "micro"];
                 for editor in identify_editor {
-                    match which(editor) {
-                        Ok(path) => {
-                            scheme.editor = String::from(path.to_str().unwrap());
-                            break;
-                        },
-                        _ => {},
+                    if let Ok(path) = which(editor) {
+                        scheme.editor = String::from(path.to_str().unwrap());
+                        break;
                     }
-
                 }
             }
         }
 
-        if let Ok(state) = config.get_bool("always-overwrite") {
-            scheme.always_overwrite = state;
+        // "always-overwrite" is the old name of overwrite = "overwrite"
+        if let Ok(true) = config.get_bool("always-overwrite") {
+            scheme.overwrite = Policy::Overwrite;
+        }
+        if let Some(policy) = config.get_string("overwrite").ok().and_then(|name| Policy::from_name(&name)) {
+            scheme.overwrite = policy;
+        }
+        if let Some(policy) = config.get_string("symlinks").ok().and_then(|name| LinkPolicy::from_name(&name)) {
+            scheme.links = policy;
+        }
+
+        if let Ok(state) = config.get_bool("mouse") {
+            scheme.mouse = state;
+        }
+
+        if let Ok(state) = config.get_bool("marquee") {
+            scheme.marquee = state;
+        }
+
+        if let Ok(state) = config.get_bool("keep-files") {
+            scheme.keep_files = state;
+        }
+
+        if let Ok(state) = config.get_bool("cache") {
+            scheme.cache = state;
+        }
+
+        if let Ok(size) = config.get::<u64>("cache-size") {
+            scheme.cache_size = size * 1024 * 1024;
+        }
+
+        if let Ok(size) = config.get::<usize>("preview-size") {
+            scheme.preview_size = size * 1024;
+        }
+
+        if let Ok(openers) = config.get_table("openers") {
+            for (pattern, value) in openers {
+                let commands = match value.clone().into_array() {
+                    Ok(list) => list.into_iter().filter_map(|command| command.into_string().ok()).collect(),
+                    Err(_) => value.into_string().into_iter().collect::<Vec<String>>(),
+                };
+
+                for command in commands {
+                    scheme.openers.push(Opener::new(&pattern, command.trim()));
+                }
+            }
+            scheme.openers.sort_by(|a, b| (a.priority(), &a.pattern).cmp(&(b.priority(), &b.pattern)));
         }
 
         scheme
@@ -0,0 +1,392 @@
+use crossterm::{
+    cursor::MoveTo,
+    terminal::{BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate},
+    QueueableCommand,
+};
+use std::{cell::RefCell, io::{self, Write}};
+use unicode_width::UnicodeWidthChar;
+
+// Colors and attributes of a cell, kept as the SGR parameters that produce them
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Style {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub attributes: Vec<u8>,
+}
+
+impl Style {
+    fn apply(&mut self, params: &str) {
+        let codes: Vec<&str> = params.split(';').collect();
+        let mut index = 0;
+        while index < codes.len() {
+            let code: u8 = codes[index].parse().unwrap_or(0);
+            match code {
+                0 => *self = Style::default(),
+                38 | 48 => {
+                    // 38;5;n and 38;2;r;g;b take the next parameters with them
+                    let size = match codes.get(index + 1) {
+                        Some(&"5") => 3,
+                        Some(&"2") => 5,
+                        _ => 1,
+                    };
+                    let end = (index + size).min(codes.len());
+                    let color = codes[index..end].join(";");
+                    if code == 38 {
+                        self.fg = Some(color);
+                    } else {
+                        self.bg = Some(color);
+                    }
+                    index = end;
+                    continue;
+                },
+                39 => self.fg = None,
+                49 => self.bg = None,
+                30..=37 | 90..=97 => self.fg = Some(code.to_string()),
+                40..=47 | 100..=107 => self.bg = Some(code.to_string()),
+                22 => self.attributes.retain(|attribute| *attribute != 1 && *attribute != 2),
+                23..=29 => self.attributes.retain(|attribute| *attribute != code - 20),
+                _ => {
+                    if !self.attributes.contains(&code) {
+                        self.attributes.push(code);
+                        self.attributes.sort();
+                    }
+                },
+            }
+            index += 1;
+        }
+    }
+
+    pub fn sequence(&self) -> String {
+        let mut params = vec![String::from("0")];
+        params.extend(self.attributes.iter().map(|attribute| attribute.to_string()));
+        params.extend(self.fg.iter().cloned());
+        params.extend(self.bg.iter().cloned());
+        format!("\x1b[{}m", params.join(";"))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cell {
+    // Empty for the second column of a wide character
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: String::from(" "),
+            style: Style::default(),
+        }
+    }
+}
+
+struct State {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    // What the terminal shows, None when it has to be painted from scratch
+    shown: Option<Vec<Cell>>,
+    shown_cursor: (u16, u16),
+    // Rows written since the last flush, the rest cannot differ from what is shown
+    dirty: Vec<bool>,
+    x: u16,
+    y: u16,
+    style: Style,
+    pending: Vec<u8>,
+}
+
+impl State {
+    fn index(&self, x: u16, y: u16) -> usize {
+        usize::from(y) * usize::from(self.width) + usize::from(x)
+    }
+
+    fn clear(&mut self) {
+        self.cells = vec![Cell::default(); usize::from(self.width) * usize::from(self.height)];
+        self.dirty = vec![true; usize::from(self.height)];
+    }
+
+    // A wide character cut in two by a write leaves nothing of itself, or the terminal would
+    // draw the whole glyph and shift the rest of the row
+    fn split_wide(&mut self, x: u16, y: u16) {
+        let row = self.index(0, y);
+        let mut start = usize::from(x);
+        while start > 0 && self.cells[row + start].symbol.is_empty() {
+            start -= 1;
+        }
+        let mut end = usize::from(x) + 1;
+        while end < usize::from(self.width) && self.cells[row + end].symbol.is_empty() {
+            end += 1;
+        }
+        if end - start > 1 {
+            for cell in &mut self.cells[row + start..row + end] {
+                cell.symbol = String::from(" ");
+            }
+        }
+    }
+
+    fn put(&mut self, character: char) {
+        if self.y >= self.height {
+            return;
+        }
+
+        let size = character.width().unwrap_or(0) as u16;
+        if size == 0 {
+            // Combining characters join the previous cell
+            if self.x > 0 {
+                let index = self.index(self.x - 1, self.y);
+                self.cells[index].symbol.push(character);
+            }
+            return;
+        }
+        if self.x + size > self.width {
+            self.x = self.width;
+            return;
+        }
+
+        self.split_wide(self.x, self.y);
+        self.split_wide(self.x + size - 1, self.y);
+        let index = self.index(self.x, self.y);
+        self.dirty[usize::from(self.y)] = true;
+        self.cells[index] = Cell {
+            symbol: character.to_string(),
+            style: self.style.clone(),
+        };
+        for offset in 1..usize::from(size) {
+            self.cells[index + offset] = Cell {
+                symbol: String::new(),
+                style: self.style.clone(),
+            };
+        }
+        self.x += size;
+    }
+
+    fn control(&mut self, params: &str, command: char) {
+        match command {
+            'H' => {
+                let mut numbers = params.split(';').map(|number| number.parse::<u16>().unwrap_or(1).max(1));
+                self.y = (numbers.next().unwrap_or(1) - 1).min(self.height.saturating_sub(1));
+                self.x = (numbers.next().unwrap_or(1) - 1).min(self.width.saturating_sub(1));
+            },
+            'm' => self.style.apply(if params.is_empty() { "0" } else { params }),
+            'K' => {
+                let start = match params {
+                    "2" => 0,
+                    "1" => return,
+                    _ => self.x,
+                };
+                if self.y >= self.height || start >= self.width {
+                    return;
+                }
+                self.split_wide(start, self.y);
+                self.dirty[usize::from(self.y)] = true;
+                for x in start..self.width {
+                    let index = self.index(x, self.y);
+                    self.cells[index] = Cell { symbol: String::from(" "), style: self.style.clone() };
+                }
+            },
+            'J' if params == "2" || params == "3" => {
+                self.clear();
+                self.shown = None;
+            },
+            _ => {},
+        }
+    }
+
+    fn parse(&mut self) {
+        let bytes = std::mem::take(&mut self.pending);
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] == 0x1b {
+                // Only CSI sequences are understood, anything else is skipped
+                let Some(&next) = bytes.get(index + 1) else {
+                    break;
+                };
+                if next != b'[' {
+                    index += 2;
+                    continue;
+                }
+                let Some(end) = bytes[index + 2..].iter().position(|byte| (0x40..=0x7e).contains(byte)) else {
+                    break;
+                };
+                let params = String::from_utf8_lossy(&bytes[index + 2..index + 2 + end]).into_owned();
+                self.control(&params, bytes[index + 2 + end] as char);
+                index += end + 3;
+                continue;
+            }
+
+            let size = match bytes[index] {
+                byte if byte < 0x80 => 1,
+                byte if byte >= 0xf0 => 4,
+                byte if byte >= 0xe0 => 3,
+                _ => 2,
+            };
+            if index + size > bytes.len() {
+                break;
+            }
+            match std::str::from_utf8(&bytes[index..index + size]) {
+                Ok(character) => {
+                    let character = character.chars().next().unwrap();
+                    match character {
+                        '\r' => self.x = 0,
+                        '\n' => self.y += 1,
+                        character if character.is_control() => {},
+                        character => self.put(character),
+                    }
+                },
+                Err(_) => self.put(char::REPLACEMENT_CHARACTER),
+            }
+            index += size;
+        }
+        self.pending = bytes[index..].to_vec();
+    }
+}
+
+// An in-memory terminal, everything is drawn here and only the differences reach the real one
+pub struct Screen {
+    state: RefCell<State>,
+}
+
+impl Screen {
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut state = State {
+            width,
+            height,
+            cells: vec![],
+            shown: None,
+            shown_cursor: (0, 0),
+            dirty: vec![],
+            x: 0,
+            y: 0,
+            style: Style::default(),
+            pending: vec![],
+        };
+        state.clear();
+        Self { state: RefCell::new(state) }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        let state = self.state.borrow();
+        (state.width, state.height)
+    }
+
+    pub fn resize(&self, width: u16, height: u16) {
+        let mut state = self.state.borrow_mut();
+        state.width = width;
+        state.height = height;
+        state.x = state.x.min(width.saturating_sub(1));
+        state.y = state.y.min(height.saturating_sub(1));
+        state.clear();
+        state.shown = None;
+    }
+
+    // The terminal was used by someone else, everything has to be painted again
+    pub fn invalidate(&self) {
+        let mut state = self.state.borrow_mut();
+        state.shown = None;
+        state.dirty.fill(true);
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        let state = self.state.borrow();
+        (state.x, state.y)
+    }
+
+    pub fn cell(&self, x: u16, y: u16) -> Cell {
+        let state = self.state.borrow();
+        state.cells[state.index(x, y)].clone()
+    }
+
+    pub fn line(&self, y: u16) -> String {
+        let state = self.state.borrow();
+        let start = state.index(0, y);
+        state.cells[start..start + usize::from(state.width)].iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let height = self.size().1;
+        (0..height).map(|y| self.line(y)).collect()
+    }
+
+    // Nothing is written when nothing changed since the last flush
+    pub fn flush_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let full = state.shown.is_none();
+        let cursor = (state.x, state.y);
+        if !full && !state.dirty.contains(&true) && state.shown_cursor == cursor {
+            return Ok(());
+        }
+
+        let mut shown = state.shown.take().unwrap_or_else(|| vec![Cell::default(); state.cells.len()]);
+        let mut changes = vec![];
+        if full {
+            changes.write_all(b"\x1b[0m")?;
+            changes.queue(Clear(ClearType::All))?;
+        }
+
+        // Moves and styles are only sent when the previous cell does not already leave them right
+        let mut position = None;
+        let mut style = None;
+        for y in 0..state.height {
+            if !state.dirty[usize::from(y)] {
+                continue;
+            }
+            for x in 0..state.width {
+                let index = state.index(x, y);
+                let cell = &state.cells[index];
+                if shown[index] == *cell || cell.symbol.is_empty() {
+                    continue;
+                }
+                if position != Some((x, y)) {
+                    changes.queue(MoveTo(x, y))?;
+                }
+                if style != Some(&cell.style) {
+                    changes.write_all(cell.style.sequence().as_bytes())?;
+                    style = Some(&cell.style);
+                }
+                changes.write_all(cell.symbol.as_bytes())?;
+                let size = 1 + state.cells[index + 1..].iter()
+                    .take(usize::from(state.width - x - 1))
+                    .take_while(|next| next.symbol.is_empty())
+                    .count() as u16;
+                position = Some((x + size, y));
+            }
+        }
+
+        if !changes.is_empty() || state.shown_cursor != cursor {
+            out.queue(BeginSynchronizedUpdate)?;
+            out.write_all(&changes)?;
+            out.write_all(b"\x1b[0m")?;
+            out.queue(MoveTo(cursor.0, cursor.1))?;
+            out.queue(EndSynchronizedUpdate)?;
+            out.flush()?;
+        }
+
+        for y in 0..state.height {
+            if state.dirty[usize::from(y)] {
+                let start = state.index(0, y);
+                let end = start + usize::from(state.width);
+                shown[start..end].clone_from_slice(&state.cells[start..end]);
+            }
+        }
+        state.shown = Some(shown);
+        state.shown_cursor = cursor;
+        state.dirty.fill(false);
+        Ok(())
+    }
+}
+
+impl Write for &Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        state.pending.extend_from_slice(buf);
+        state.parse();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
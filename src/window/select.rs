@@ -0,0 +1,94 @@
+use crate::window::palette::fuzzy_score;
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub struct Select {
+    pub quest: String,
+    pub items: Vec<String>,
+    // None when the list cannot be filtered, then digits choose the first nine items
+    pub filter: Option<String>,
+    // Position in the matches, not in the items
+    pub selected: usize,
+    pub scroll: usize,
+}
+
+impl Select {
+    pub fn new(quest: &str, items: Vec<String>) -> Self {
+        Self {
+            quest: String::from(quest),
+            items,
+            filter: None,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn filtered(quest: &str, items: Vec<String>) -> Self {
+        let mut select = Self::new(quest, items);
+        select.filter = Some(String::new());
+        select
+    }
+
+    // Indexes of the items that match the filter, best first
+    pub fn matches(&self) -> Vec<usize> {
+        let filter = self.filter.as_deref().unwrap_or_default();
+        let mut found: Vec<(usize, usize)> = self.items.iter()
+            .enumerate()
+            .filter_map(|(index, item)| fuzzy_score(filter, item).map(|score| (score, index)))
+            .collect();
+        found.sort();
+        found.into_iter().map(|(_, index)| index).collect()
+    }
+
+    pub fn chosen(&self) -> Option<usize> {
+        self.matches().get(self.selected).copied()
+    }
+
+    pub fn move_by(&mut self, rows: isize) {
+        let last = self.matches().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(rows).min(last);
+    }
+
+    // Keeps the selected item between the visible rows
+    pub fn scroll_to(&mut self, rows: usize) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        self.scroll = self.scroll.min(self.matches().len().saturating_sub(rows));
+    }
+
+    // Returns false for the keys the list does not use, Enter and Esc are left to the caller
+    pub fn key(&mut self, key: &KeyEvent, page: usize) -> bool {
+        let page = page.max(1) as isize;
+        match key.code {
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down | KeyCode::Tab => self.move_by(1),
+            KeyCode::BackTab => self.move_by(-1),
+            KeyCode::PageUp => self.move_by(-page),
+            KeyCode::PageDown => self.move_by(page),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.move_by(isize::MAX),
+            KeyCode::Backspace => match self.filter.as_mut() {
+                Some(filter) => {
+                    filter.pop();
+                    self.selected = 0;
+                },
+                None => return false,
+            },
+            KeyCode::Char(ch) => match self.filter.as_mut() {
+                Some(filter) => {
+                    filter.push(ch);
+                    self.selected = 0;
+                },
+                None => match ch {
+                    'k' => self.move_by(-1),
+                    'j' => self.move_by(1),
+                    _ => return false,
+                },
+            },
+            _ => return false,
+        }
+        true
+    }
+}
@@ -0,0 +1,92 @@
+use crate::files::workspace;
+use crossterm::{cursor, event, terminal, QueueableCommand};
+use signal_hook::{consts::{SIGHUP, SIGINT, SIGTERM}, iterator::Signals};
+use std::{
+    fs::File,
+    io::{self, stdout, Write},
+    mem::ManuallyDrop,
+    os::fd::FromRawFd,
+    panic, process,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+// What leave() has to undo, the hooks read it from any thread
+static OPEN: AtomicBool = AtomicBool::new(false);
+static MOUSE: AtomicBool = AtomicBool::new(false);
+// Another program has the terminal, Ctrl-C is meant for it
+static AWAY: AtomicBool = AtomicBool::new(false);
+
+pub fn enter(mouse: bool) -> io::Result<()> {
+    let mut out = stdout();
+    out.queue(terminal::EnterAlternateScreen)?;
+    out.queue(event::EnableBracketedPaste)?;
+    if mouse {
+        out.queue(event::EnableMouseCapture)?;
+    }
+    terminal::enable_raw_mode()?;
+    out.flush()?;
+
+    MOUSE.store(mouse, Ordering::SeqCst);
+    OPEN.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+// Gives the terminal back as it was found, only the first call does something
+pub fn leave() {
+    if !OPEN.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    let mut sequences = vec![];
+    if MOUSE.load(Ordering::SeqCst) {
+        let _ = sequences.queue(event::DisableMouseCapture);
+    }
+    let _ = sequences.queue(event::DisableBracketedPaste);
+    let _ = sequences.queue(terminal::LeaveAlternateScreen);
+    let _ = sequences.queue(cursor::Show);
+    let _ = terminal::disable_raw_mode();
+
+    // The main loop holds the stdout lock, the hooks write around it
+    let mut out = ManuallyDrop::new(unsafe { File::from_raw_fd(1) });
+    let _ = out.write_all(&sequences);
+    let _ = out.flush();
+}
+
+// Runs something while the terminal belongs to another program, SIGINT is left to that program
+pub fn away<T>(run: impl FnOnce() -> T) -> T {
+    AWAY.store(true, Ordering::SeqCst);
+    let result = run();
+    AWAY.store(false, Ordering::SeqCst);
+    result
+}
+
+// The terminal is restored and the workspace removed before a panic or SIGINT, SIGTERM and SIGHUP end the program
+pub fn restore_on_exit() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        leave();
+        workspace::remove_pending();
+        previous(info);
+    }));
+
+    if let Ok(mut signals) = Signals::new([SIGINT, SIGTERM, SIGHUP]) {
+        thread::spawn(move || {
+            for signal in signals.forever() {
+                if signal == SIGINT && AWAY.load(Ordering::SeqCst) {
+                    continue;
+                }
+                leave();
+                workspace::remove_pending();
+                let name = match signal {
+                    SIGINT => "SIGINT",
+                    SIGTERM => "SIGTERM",
+                    _ => "SIGHUP",
+                };
+                // After SIGHUP there may be no terminal left to print to
+                let _ = writeln!(io::stderr(), "Stopped by {}", name);
+                process::exit(128 + signal);
+            }
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub const ELLIPSIS: &str = "…";
+
+// Columns the text takes in the terminal, wide characters take two
+pub fn width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+fn grapheme_width(grapheme: &str) -> usize {
+    UnicodeWidthStr::width(grapheme)
+}
+
+// The longest start of the text that fits in the width, never splitting a character
+pub fn take(text: &str, max: usize) -> String {
+    let mut taken = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let size = grapheme_width(grapheme);
+        if used + size > max {
+            break;
+        }
+        used += size;
+        taken += grapheme;
+    }
+    taken
+}
+
+// The longest end of the text that fits in the width
+pub fn take_last(text: &str, max: usize) -> String {
+    let mut taken = vec![];
+    let mut used = 0;
+    for grapheme in text.graphemes(true).rev() {
+        let size = grapheme_width(grapheme);
+        if used + size > max {
+            break;
+        }
+        used += size;
+        taken.push(grapheme);
+    }
+    taken.into_iter().rev().collect()
+}
+
+// Skips the first columns of the text, a wide character cut in half is skipped entirely
+pub fn skip(text: &str, columns: usize) -> String {
+    let mut skipped = 0;
+    let mut rest = String::new();
+    for grapheme in text.graphemes(true) {
+        if skipped < columns {
+            skipped += grapheme_width(grapheme);
+            continue;
+        }
+        rest += grapheme;
+    }
+    rest
+}
+
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return String::from(text);
+    }
+    if max == 0 {
+        return String::new();
+    }
+    take(text, max - 1) + ELLIPSIS
+}
+
+pub fn truncate_left(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return String::from(text);
+    }
+    if max == 0 {
+        return String::new();
+    }
+    String::from(ELLIPSIS) + take_last(text, max - 1).as_str()
+}
+
+pub fn pad(text: &str, max: usize) -> String {
+    String::from(text) + " ".repeat(max.saturating_sub(width(text))).as_str()
+}
+
+// Splits the text in rows of at most the given width
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let mut rows = vec![];
+    let mut row = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let size = grapheme_width(grapheme);
+        if used + size > max && !row.is_empty() {
+            rows.push(std::mem::take(&mut row));
+            used = 0;
+        }
+        used += size;
+        row += grapheme;
+    }
+
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(row);
+    }
+    rows
+}
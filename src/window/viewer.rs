@@ -0,0 +1,423 @@
+use crate::{
+    window::preview::{hex_lines, hex_row_size, is_binary, wrap},
+    zip_manager::reader::{MemberReader, PipedCommand},
+};
+
+pub const VIEWER_CHUNK: usize = 64 * 1024;
+// Bytes kept of a member or a command, what comes after is not shown
+pub const VIEWER_LIMIT: usize = 64 * 1024 * 1024;
+// Chunks read at a time when going to the end or searching, keys are looked at in between
+pub const VIEWER_STEP: usize = 16;
+const READING: &str = "Reading... any key stops";
+
+pub const VIEWER_KEYS: [(&str, &str); 8] = [
+    ("up, k, down, j", "Scroll one line"),
+    ("pageup, b, pagedown, space", "Scroll one page"),
+    ("home, g, end, G", "Go to the start or the end"),
+    ("tab", "Switch between text and hex"),
+    ("/", "Search"),
+    ("n, N", "Next or previous match"),
+    ("enter", "Scroll one line down"),
+    ("q, esc", "Back to the file list"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ViewerMode {
+    Text,
+    Hex,
+}
+
+// Work that reads ahead and is done a few chunks at a time
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Pending {
+    Bottom,
+    // Line, or byte in hex, where the search goes on
+    Find(usize),
+}
+
+pub enum ViewerSource {
+    Member(MemberReader),
+    Memory(Vec<u8>),
+    Pipe(PipedCommand),
+}
+
+pub struct Viewer {
+    pub title: String,
+    pub source: ViewerSource,
+    pub mode: ViewerMode,
+    pub scroll: usize,
+    pub lines: Vec<usize>,
+    pub search: String,
+    pub prompt: Option<String>,
+    pub message: Option<String>,
+    pub pending: Option<Pending>,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Viewer {
+    fn new(title: &str, source: ViewerSource, width: u16, height: u16) -> Self {
+        let mut viewer = Self {
+            title: String::from(title),
+            source,
+            mode: ViewerMode::Text,
+            scroll: 0,
+            lines: vec![0],
+            search: String::new(),
+            prompt: None,
+            message: None,
+            pending: None,
+            width,
+            height,
+        };
+
+        viewer.index_lines(0);
+        viewer.fill_more();
+        if is_binary(viewer.bytes()) {
+            viewer.mode = ViewerMode::Hex;
+        }
+        viewer
+    }
+
+    pub fn member(archive: &str, member: &str, width: u16, height: u16) -> Result<Self, String> {
+        let reader = MemberReader::open(archive, member)?;
+        Ok(Self::new(member, ViewerSource::Member(reader), width, height))
+    }
+
+    pub fn memory(title: &str, content: Vec<u8>, width: u16, height: u16) -> Self {
+        Self::new(title, ViewerSource::Memory(content), width, height)
+    }
+
+    pub fn pipe(title: &str, piped: PipedCommand, width: u16, height: u16) -> Self {
+        Self::new(title, ViewerSource::Pipe(piped), width, height)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match &self.source {
+            ViewerSource::Member(reader) => &reader.content,
+            ViewerSource::Memory(content) => content,
+            ViewerSource::Pipe(piped) => &piped.content,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match &self.source {
+            ViewerSource::Member(reader) => reader.is_finished() || self.is_cut(),
+            ViewerSource::Memory(_) => true,
+            ViewerSource::Pipe(piped) => piped.is_finished() || self.is_cut(),
+        }
+    }
+
+    // The rest of a huge member or output is left unread
+    pub fn is_cut(&self) -> bool {
+        !matches!(self.source, ViewerSource::Memory(_)) && self.bytes().len() >= VIEWER_LIMIT
+    }
+
+    // A command that is still running may print more at any moment
+    pub fn is_waiting(&self) -> bool {
+        matches!(self.source, ViewerSource::Pipe(_)) && !self.is_finished()
+    }
+
+    // Takes what a command printed meanwhile, true when there is something new to show
+    pub fn update(&mut self) -> bool {
+        if self.pending.is_some() {
+            return self.step();
+        }
+        if !self.is_waiting() {
+            return false;
+        }
+        let before = self.bytes().len();
+        self.rows();
+        if before == 0 && is_binary(self.bytes()) {
+            self.mode = ViewerMode::Hex;
+        }
+        self.bytes().len() > before || self.is_finished()
+    }
+
+    // Reads one more chunk of the member, returns false when there is nothing left
+    fn fill_more(&mut self) -> bool {
+        let start = self.bytes().len();
+        if self.is_cut() {
+            return false;
+        }
+        let size = (start + VIEWER_CHUNK).min(VIEWER_LIMIT);
+        match &mut self.source {
+            ViewerSource::Member(reader) => {
+                reader.fill_to(size);
+            },
+            ViewerSource::Pipe(piped) => {
+                piped.fill_to(size);
+            },
+            ViewerSource::Memory(_) => {},
+        }
+
+        self.index_lines(start);
+        self.bytes().len() > start
+    }
+
+    fn index_lines(&mut self, from: usize) {
+        let starts: Vec<usize> = self.bytes()[from..].iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+            .map(|(index, _)| from + index + 1)
+            .collect();
+        self.lines.extend(starts);
+    }
+
+    fn ensure_bytes(&mut self, size: usize) {
+        while self.bytes().len() < size && self.fill_more() {}
+    }
+
+    fn ensure_lines(&mut self, count: usize) {
+        while self.lines.len() <= count && self.fill_more() {}
+    }
+
+    pub fn line_count(&self) -> usize {
+        let last = self.lines[self.lines.len() - 1];
+        if self.is_finished() && last == self.bytes().len() && self.lines.len() > 1 {
+            self.lines.len() - 1
+        } else {
+            self.lines.len()
+        }
+    }
+
+    pub fn line(&self, index: usize) -> Option<String> {
+        if index >= self.line_count() {
+            return None;
+        }
+
+        let start = self.lines[index];
+        let end = match self.lines.get(index + 1) {
+            Some(next) => next - 1,
+            None => self.bytes().len(),
+        };
+        Some(String::from_utf8_lossy(&self.bytes()[start..end]).into_owned())
+    }
+
+    pub fn page_size(&self) -> usize {
+        usize::from(self.height).saturating_sub(2).max(1)
+    }
+
+    pub fn row_size(&self) -> usize {
+        hex_row_size(usize::from(self.width))
+    }
+
+    pub fn rows(&mut self) -> Vec<String> {
+        let width = usize::from(self.width);
+        let height = self.page_size();
+
+        match self.mode {
+            ViewerMode::Text => {
+                self.ensure_lines(self.scroll + height);
+
+                let mut rows = vec![];
+                let mut index = self.scroll;
+                while rows.len() < height {
+                    match self.line(index) {
+                        Some(line) => rows.extend(wrap(&line, width)),
+                        None => break,
+                    }
+                    index += 1;
+                }
+                rows.truncate(height);
+                rows
+            },
+            ViewerMode::Hex => {
+                let offset = self.scroll * self.row_size();
+                self.ensure_bytes(offset + height * self.row_size());
+
+                let end = self.bytes().len().min(offset + height * self.row_size());
+                let start = offset.min(end);
+                hex_lines(&self.bytes()[start..end], start, width)
+            },
+        }
+    }
+
+    fn last_scroll(&mut self) -> usize {
+        match self.mode {
+            ViewerMode::Text => self.line_count().saturating_sub(self.page_size()),
+            ViewerMode::Hex => {
+                let rows = self.bytes().len().div_ceil(self.row_size());
+                rows.saturating_sub(self.page_size())
+            },
+        }
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        match self.mode {
+            ViewerMode::Text => self.ensure_lines(self.scroll + amount + self.page_size()),
+            ViewerMode::Hex => self.ensure_bytes((self.scroll + amount + self.page_size()) * self.row_size()),
+        }
+        self.scroll = (self.scroll + amount).min(self.last_scroll());
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+
+    pub fn scroll_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_bottom(&mut self) {
+        self.pending = Some(Pending::Bottom);
+        self.step();
+    }
+
+    // Goes on with the pending work for a while, true when there is something new to show
+    pub fn step(&mut self) -> bool {
+        match self.pending.take() {
+            None => return false,
+            Some(Pending::Bottom) => {
+                let finished = (0..VIEWER_STEP).any(|_| !self.fill_more());
+                if finished {
+                    self.scroll = self.last_scroll();
+                } else {
+                    self.pending = Some(Pending::Bottom);
+                }
+            },
+            Some(Pending::Find(from)) => match self.mode {
+                ViewerMode::Text => self.find_line(from),
+                ViewerMode::Hex => self.find_bytes(from),
+            },
+        }
+
+        if self.pending.is_some() {
+            self.message = Some(String::from(READING));
+        } else if self.message.as_deref() == Some(READING) {
+            self.message = None;
+        }
+        true
+    }
+
+    // Returns true when there was something to stop
+    pub fn stop(&mut self) -> bool {
+        self.message = None;
+        self.pending.take().is_some()
+    }
+
+    pub fn toggle_mode(&mut self) {
+        match self.mode {
+            ViewerMode::Text => {
+                let offset = self.lines.get(self.scroll).copied().unwrap_or(0);
+                self.mode = ViewerMode::Hex;
+                self.scroll = offset / self.row_size();
+            },
+            ViewerMode::Hex => {
+                let offset = self.scroll * self.row_size();
+                self.mode = ViewerMode::Text;
+                self.scroll = match self.lines.binary_search(&offset) {
+                    Ok(index) => index,
+                    Err(index) => index.saturating_sub(1),
+                };
+            },
+        }
+    }
+
+    // Percentage of the member shown so far, if the whole member is known
+    pub fn position(&self) -> Option<usize> {
+        if !self.is_finished() {
+            return None;
+        }
+
+        let total = match self.mode {
+            ViewerMode::Text => self.line_count(),
+            ViewerMode::Hex => self.bytes().len().div_ceil(self.row_size()),
+        };
+        if total <= self.page_size() {
+            return Some(100);
+        }
+        Some(((self.scroll + self.page_size()) * 100 / total).min(100))
+    }
+
+    // A new search also looks at the top of the page, repeating it starts after it
+    pub fn find(&mut self, forward: bool, inclusive: bool) {
+        self.message = None;
+        if self.search.is_empty() {
+            return;
+        }
+
+        if forward {
+            let line = self.scroll + usize::from(!inclusive);
+            self.pending = Some(Pending::Find(match self.mode {
+                ViewerMode::Text => line,
+                ViewerMode::Hex => line * self.row_size(),
+            }));
+            self.step();
+            return;
+        }
+
+        let found = match self.mode {
+            ViewerMode::Text => (0..self.scroll).rev().find(|index| {
+                self.line(*index).is_some_and(|line| line.contains(self.search.as_str()))
+            }),
+            ViewerMode::Hex => {
+                let pattern = self.search.as_bytes();
+                let end = (self.scroll * self.row_size() + pattern.len() - 1).min(self.bytes().len());
+                self.bytes()[..end].windows(pattern.len())
+                    .rposition(|window| window == pattern)
+                    .map(|found| found / self.row_size())
+            },
+        };
+        match found {
+            Some(scroll) => self.scroll = scroll,
+            None => self.not_found(),
+        }
+    }
+
+    fn not_found(&mut self) {
+        self.message = Some(format!("Pattern not found: {}", self.search));
+    }
+
+    fn find_line(&mut self, from: usize) {
+        let mut chunks = VIEWER_STEP;
+        let mut index = from;
+        loop {
+            // The line is only whole once the next one started
+            while self.lines.len() <= index + 1 {
+                if chunks == 0 {
+                    self.pending = Some(Pending::Find(index));
+                    return;
+                }
+                chunks -= 1;
+                if !self.fill_more() {
+                    break;
+                }
+            }
+
+            match self.line(index) {
+                Some(line) if line.contains(self.search.as_str()) => {
+                    self.scroll = index;
+                    return;
+                },
+                Some(_) => index += 1,
+                None => return self.not_found(),
+            }
+        }
+    }
+
+    fn find_bytes(&mut self, from: usize) {
+        let pattern = self.search.clone().into_bytes();
+        let mut chunks = VIEWER_STEP;
+        let mut start = from;
+        loop {
+            if let Some(found) = self.bytes().get(start..).and_then(|rest| {
+                rest.windows(pattern.len()).position(|window| window == pattern.as_slice())
+            }) {
+                self.scroll = (start + found) / self.row_size();
+                return;
+            }
+
+            // Keep the tail, the pattern may be split between chunks
+            start = self.bytes().len().saturating_sub(pattern.len() - 1).max(start);
+            if chunks == 0 {
+                self.pending = Some(Pending::Find(start));
+                return;
+            }
+            chunks -= 1;
+            if !self.fill_more() {
+                return self.not_found();
+            }
+        }
+    }
+}
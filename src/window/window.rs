@@ -1,58 +1,133 @@
 use crate::{
-    files::folder::Folder,
+    files::{folder::Folder, entry::{self, Entry, EntryInfo}, workspace::Workspace},
     window::{
         cursor::Cursor,
         scheme::Scheme,
         handler::{Handler, HandleSituatonType},
+        preview::{PreviewState, PREVIEW_DELAY},
+        viewer::Viewer,
+        input::Input,
+        select::Select,
+        keymap::Keymap,
+        palette::Palette,
+        breadcrumbs::{self, Crumb},
+        text,
+        screen::Screen,
+        term,
     },
-    zip_manager::manager
+    zip_manager::{listing::{ListedEntry, Listing}, manager, safety::{self, Hazard}, task::Task},
 };
 use std::{
-    io::{StdoutLock, Write},
-    fs::remove_dir_all
+    cmp::Reverse,
+    collections::HashMap,
+    time::{Duration, Instant},
 };
-use crossterm::{terminal, QueueableCommand};
 use config::Config;
 
-pub struct Window<'a> {
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+pub const MESSAGE_TIME: Duration = Duration::from_secs(3);
+pub const MARQUEE_STEP: Duration = Duration::from_millis(200);
+pub const MARQUEE_PAUSE: usize = 5;
+pub const SCROLL_X_STEP: u16 = 4;
+pub const TASK_REFRESH: Duration = Duration::from_millis(100);
+
+pub struct NestedArchive {
+    pub path: String,
+    pub depth: usize,
+    pub root: Folder,
+    pub info: HashMap<String, EntryInfo>,
+    pub folders: HashMap<String, EntryInfo>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortOrder {
+    Archive,
+    Name,
+    Size,
+    Date,
+}
+
+impl SortOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            SortOrder::Archive => SortOrder::Name,
+            SortOrder::Name => SortOrder::Size,
+            SortOrder::Size => SortOrder::Date,
+            SortOrder::Date => SortOrder::Archive,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::Archive => "archive order",
+            SortOrder::Name => "by name",
+            SortOrder::Size => "by size",
+            SortOrder::Date => "by date",
+        }
+    }
+}
+
+pub struct Window {
     pub root: Folder,
     pub current: Vec<Folder>,
     pub width: u16,
     pub height: u16,
     pub scroll_x: u16,
-    pub scroll_y: u16,
+    pub scroll_y: usize,
     pub scroll_change: bool,
     pub path_change: bool,
     pub on_dialog: bool,
     pub cursor: Cursor,
     pub path: String,
+    pub nested: Vec<NestedArchive>,
+    pub info: HashMap<String, EntryInfo>,
+    pub folders: HashMap<String, EntryInfo>,
+    pub sort: SortOrder,
+    pub filter: String,
+    pub marked: Vec<(String, u64)>,
+    pub message: Option<(String, Instant)>,
     pub scheme: Scheme,
+    pub keymap: Keymap,
     pub handler: Option<Box<dyn Handler>>,
-    pub tmp_dir: String,
-    pub writer: *mut StdoutLock<'a>,
+    pub preview: PreviewState,
+    pub viewer: Option<Viewer>,
+    pub input: Option<Input>,
+    pub input_history: HashMap<&'static str, Vec<String>>,
+    pub select: Option<Select>,
+    pub task: Option<Task>,
+    pub loading: Option<Listing>,
+    pub help: Option<usize>,
+    pub palette: Palette,
+    pub last_click: Option<(usize, Instant)>,
+    pub breadcrumb: Option<usize>,
+    pub marquee: Option<(usize, Instant, usize)>,
+    pub workspace: Workspace,
+    pub screen: Screen,
+    pub terminal_open: bool,
 }
 
-impl<'a> Drop for Window<'a> {
+impl Drop for Window {
     fn drop(&mut self) {
-        terminal::disable_raw_mode().unwrap();
-        unsafe {
-            (&mut (*self.writer)).queue(terminal::LeaveAlternateScreen).unwrap();
+        if self.terminal_open {
+            term::leave();
         }
 
         self.handler.take();
-        if self.tmp_dir != "" {
-            if let Err(_) = remove_dir_all(self.tmp_dir.clone()) {
-                // TODO
-            }
+        if let Some(root) = self.workspace.root().filter(|_| self.workspace.keep) {
+            eprintln!("The extracted files are kept in '{}'", root.display());
+        }
+        if let Err(err) = self.workspace.remove() {
+            eprintln!("{}", err);
         }
     }
 }
 
-impl<'a> Window<'a> {
-    pub fn new(stdout: *mut StdoutLock<'a>, config: Config) -> Self {
-        let (width, height) = terminal::size().unwrap();
-
-        let mut window = Self {
+impl Window {
+    // Nothing is shown until the terminal is opened, so it can also draw without one
+    pub fn new(config: Config, width: u16, height: u16) -> Self {
+        let keymap = Keymap::from(&config);
+        let scheme = Scheme::from(config);
+        Self {
             root: Folder::new(""),
             current: vec![],
             width, 
@@ -64,42 +139,363 @@ impl<'a> Window<'a> {
             on_dialog: false,
             cursor: Cursor { x: 1, y: 4, need_update: false },
             path: String::new(),
-            scheme: Scheme::from(config),
+            nested: vec![],
+            info: HashMap::new(),
+            folders: HashMap::new(),
+            sort: SortOrder::Archive,
+            filter: String::new(),
+            marked: vec![],
+            message: None,
+            keymap,
+            workspace: Workspace::new(scheme.keep_files),
+            scheme,
             handler: None,
-            tmp_dir: String::new(),
-            writer: stdout,
-        };
-        window.open_window();
-        window
+            preview: PreviewState::new(),
+            viewer: None,
+            input: None,
+            input_history: HashMap::new(),
+            select: None,
+            task: None,
+            loading: None,
+            help: None,
+            palette: Palette::new(),
+            last_click: None,
+            breadcrumb: None,
+            marquee: None,
+            screen: Screen::new(width, height),
+            terminal_open: false,
+        }
     }
 
     pub fn open_window(&mut self) {
-        let out = self.get_writer();
-        out.queue(terminal::EnterAlternateScreen).unwrap();
-        out.queue(terminal::EndSynchronizedUpdate).unwrap();
-        terminal::enable_raw_mode().expect("Error al abrir la patalla");
-        out.flush().unwrap();
+        term::enter(self.scheme.mouse).expect("Error al abrir la patalla");
+        self.terminal_open = true;
+        self.screen.invalidate();
     }
 
     pub fn assign_root(&mut self, folder: Folder) {
         self.root = folder.clone();
         self.current = vec![folder];
+        self.arrange_current();
     }
 
     pub fn assing_manager(&mut self, manager: manager::ZipManager) {
         self.assign_path(manager.get_path());
         self.assign_root(manager.get_root());
-    } 
+        self.info = manager.get_info();
+        self.folders = entry::folder_totals(&self.info);
+    }
 
-    pub fn get_current(&self) -> &Folder {
-        if self.current.len() == 0 {
+    // Entries of the main archive that arrive while it is still being listed
+    pub fn add_listed(&mut self, entries: Vec<ListedEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        // Folders of nested archives are left alone
+        let depth = self.nested.first().map_or(self.current.len(), |nested| nested.depth);
+        let prefixes: Vec<String> = (1..depth).map(|depth| self.plain_from(0).split('/')
+            .skip(1)
+            .take(depth)
+            .collect::<Vec<_>>()
+            .join("/") + "/")
+            .collect();
 
+        for (name, entry_type, info) in entries {
+            self.root.add_entry(&name, &entry_type);
+            self.current[0].add_entry(&name, &entry_type);
+            for (index, prefix) in prefixes.iter().enumerate() {
+                if let Some(rest) = name.strip_prefix(prefix.as_str()) {
+                    self.current[index + 1].add_entry(rest, &entry_type);
+                }
+            }
+            entry::add_to_folders(&mut self.folders, &name, &info);
+            self.info.insert(name, info);
         }
+
+        // Only the shown folder keeps up, the others are arranged when going back to them
+        if self.current.len() <= depth && (self.sort != SortOrder::Archive || !self.filter.is_empty()) {
+            self.arrange_current();
+        }
+        self.scroll_change = true;
+        self.cursor.need_update = true;
+    }
+
+    pub fn assign_nested_manager(&mut self, manager: manager::ZipManager, name: &str) {
+        let mut root = manager.get_root();
+        root.name = String::from(name);
+
+        let info = manager.get_info();
+        self.nested.push(NestedArchive {
+            path: manager.get_path(),
+            depth: self.current.len(),
+            root: root.clone(),
+            folders: entry::folder_totals(&info),
+            info,
+        });
+        self.set_current(root);
+    }
+
+    pub fn get_current(&self) -> &Folder {
         &self.current[self.current.len() - 1]
     }
+
+    pub fn visible_rows(&self) -> u16 {
+        self.height.saturating_sub(5)
+    }
+
+    pub fn scroll_by(&mut self, amount: i32) {
+        let last = self.get_current().content.len().saturating_sub(usize::from(self.visible_rows()));
+        let scroll = (self.scroll_y as i64 + i64::from(amount)).clamp(0, last as i64) as usize;
+        if scroll != self.scroll_y {
+            self.scroll_y = scroll;
+            self.scroll_change = true;
+            self.cursor.need_update = true;
+        }
+    }
+
+    // Returns true when the same row was clicked twice in a short time
+    pub fn click(&mut self, row: u16) -> bool {
+        let index = usize::from(row - 4) + self.scroll_y;
+        self.set_cursor(self.cursor.x, row);
+
+        let double = matches!(self.last_click, Some((last, at)) if last == index && at.elapsed() <= DOUBLE_CLICK);
+        self.last_click = if double { None } else { Some((index, Instant::now())) };
+        double
+    }
+
+    pub fn get_selected(&self) -> Option<&Entry> {
+        self.get_current().content.get(self.selected_index())
+    }
+
+    pub fn selected_index(&self) -> usize {
+        usize::from(self.cursor.y - 4) + self.scroll_y
+    }
+
+    pub fn archive_info(&self) -> &HashMap<String, EntryInfo> {
+        match self.nested.last() {
+            Some(nested) => &nested.info,
+            None => &self.info,
+        }
+    }
+
+    // Path of the entry inside the innermost archive, without the leading "/"
+    pub fn member_path(&self, name: &str) -> String {
+        let path = self.plain_member() + "/" + name;
+        String::from(&path[1..])
+    }
+
+    // Size and newest date of everything inside each folder, kept as the entries are listed
+    pub fn archive_folders(&self) -> &HashMap<String, EntryInfo> {
+        match self.nested.last() {
+            Some(nested) => &nested.folders,
+            None => &self.folders,
+        }
+    }
+
+    // Folders add up the size of everything inside them
+    pub fn entry_size(&self, entry: &Entry) -> u64 {
+        match entry {
+            Entry::File(name) => self.archive_info()
+                .get(&self.member_path(name))
+                .map_or(0, |info| info.size),
+            Entry::Folder(folder) => self.archive_folders()
+                .get(&self.member_path(&folder.name))
+                .map_or(0, |info| info.size),
+        }
+    }
+
+    // Folders take the date of the newest entry inside them
+    pub fn entry_date(&self, entry: &Entry) -> String {
+        let path = self.member_path(Window::entry_name(entry));
+        let info = match entry {
+            Entry::File(_) => self.archive_info().get(&path),
+            Entry::Folder(_) => self.archive_folders().get(&path).or(self.archive_info().get(&path)),
+        };
+        info.map_or(String::new(), |info| info.date.clone())
+    }
+
+    // Entries that cannot be extracted where they say, the trailing "/" also checks the folder itself: nameless (absolute paths) or a link
+    pub fn hazard(&self, entry: &Entry) -> Option<Hazard> {
+        match entry {
+            Entry::File(name) => safety::hazard(self.archive_info(), &self.member_path(name)),
+            Entry::Folder(folder) => safety::hazard(self.archive_info(), &(self.member_path(&folder.name) + "/")),
+        }
+    }
+
+    pub fn entry_name(entry: &Entry) -> &str {
+        match entry {
+            Entry::File(name) => name,
+            Entry::Folder(folder) => &folder.name,
+        }
+    }
+
+    pub fn is_marked(&self, entry: &Entry) -> bool {
+        let path = self.plain_current() + "/" + Self::entry_name(entry);
+        self.marked.iter().any(|(marked, _)| *marked == path)
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let Some(entry) = self.get_selected() else {
+            return;
+        };
+
+        let path = self.plain_current() + "/" + Self::entry_name(entry);
+        let size = self.entry_size(entry);
+        match self.marked.iter().position(|(marked, _)| *marked == path) {
+            Some(index) => {
+                self.marked.remove(index);
+            },
+            None => self.marked.push((path, size)),
+        }
+        self.scroll_change = true;
+    }
+
+    // Columns left for the name of the entry after its bullet
+    pub fn name_room(&self, entry: &Entry) -> usize {
+        let bullet = match entry {
+            Entry::File(_) => &self.scheme.file_bullet,
+            Entry::Folder(_) => &self.scheme.folder_bullet,
+        };
+        usize::from(self.list_width().saturating_sub(2)).saturating_sub(text::width(bullet))
+    }
+
+    fn max_scroll_x(&self) -> u16 {
+        self.get_current().content.iter()
+            .map(|entry| text::width(Self::entry_name(entry)).saturating_sub(self.name_room(entry)))
+            .max()
+            .unwrap_or(0)
+            .min(usize::from(u16::MAX)) as u16
+    }
+
+    pub fn scroll_left(&mut self) {
+        if self.scroll_x > 0 {
+            self.scroll_x = self.scroll_x.saturating_sub(SCROLL_X_STEP);
+            self.scroll_change = true;
+        }
+    }
+
+    pub fn scroll_right(&mut self) {
+        let scroll = (self.scroll_x + SCROLL_X_STEP).min(self.max_scroll_x());
+        if scroll != self.scroll_x {
+            self.scroll_x = scroll;
+            self.scroll_change = true;
+        }
+    }
+
+    // Where the name of the selected entry starts while it scrolls by itself
+    pub fn marquee_offset(&self, room: usize) -> Option<usize> {
+        let (index, since, _) = self.marquee?;
+        let entry = self.get_selected()?;
+        let overflow = text::width(Self::entry_name(entry)).saturating_sub(room);
+        if !self.scheme.marquee || index != self.selected_index() || overflow == 0 {
+            return None;
+        }
+
+        let step = (since.elapsed().as_millis() / MARQUEE_STEP.as_millis()) as usize;
+        let position = step % (overflow + 2 * MARQUEE_PAUSE);
+        Some(position.saturating_sub(MARQUEE_PAUSE).min(overflow))
+    }
+
+    // Returns true when the selected row has to be drawn again
+    pub fn marquee_tick(&mut self) -> bool {
+        if !self.scheme.marquee {
+            return false;
+        }
+        let Some(entry) = self.get_selected() else {
+            return false;
+        };
+
+        let room = self.name_room(entry);
+        let index = self.selected_index();
+        match self.marquee {
+            Some((selected, _, _)) if selected == index => {
+                let offset = self.marquee_offset(room).unwrap_or(0);
+                let changed = self.marquee.map(|(_, _, last)| last != offset).unwrap_or(false);
+                if let Some(marquee) = self.marquee.as_mut() {
+                    marquee.2 = offset;
+                }
+                changed
+            },
+            // The name that stopped scrolling goes back to its place
+            Some((_, _, last)) => {
+                self.marquee = Some((index, Instant::now(), 0));
+                last != 0
+            },
+            None => {
+                self.marquee = Some((index, Instant::now(), 0));
+                false
+            },
+        }
+    }
+
+    pub fn remember_input(&mut self, input: &Input) {
+        let value = input.value.trim();
+        let Some(purpose) = input.purpose else {
+            return;
+        };
+        if value.is_empty() {
+            return;
+        }
+
+        let history = self.input_history.entry(purpose).or_default();
+        history.retain(|old| old != value);
+        history.push(String::from(value));
+    }
+
+    pub fn history_for(&self, purpose: &str) -> Vec<String> {
+        self.input_history.get(purpose).cloned().unwrap_or_default()
+    }
+
+    // Dialogs and full screen views that stop the list from being updated
+    pub fn is_modal(&self) -> bool {
+        self.on_dialog || self.viewer.is_some() || self.input.is_some() || self.select.is_some() || self.task.is_some()
+            || self.palette.visible || self.help.is_some()
+    }
+
+    // How long the loop can wait for input before something on screen has to change
+    pub fn next_wakeup(&self) -> Option<Duration> {
+        let mut deadlines = vec![];
+        if self.task.is_some() || self.loading.is_some() || self.viewer.as_ref().is_some_and(Viewer::is_waiting)
+            || self.preview.is_loading() {
+            deadlines.push(Instant::now() + TASK_REFRESH);
+        }
+        if self.viewer.as_ref().is_some_and(|viewer| viewer.pending.is_some()) {
+            deadlines.push(Instant::now());
+        }
+        if let Some((_, since)) = &self.message {
+            deadlines.push(*since + MESSAGE_TIME);
+        }
+        if let Some((_, since)) = &self.preview.wanted {
+            if self.preview.enabled && !self.on_dialog && self.viewer.is_none() {
+                deadlines.push(*since + PREVIEW_DELAY);
+            }
+        }
+        if let (Some((_, since, _)), Some(entry)) = (self.marquee, self.get_selected()) {
+            if !self.is_modal() && self.marquee_offset(self.name_room(entry)).is_some() {
+                let step = MARQUEE_STEP.as_millis();
+                let left = step - since.elapsed().as_millis() % step;
+                deadlines.push(Instant::now() + Duration::from_millis(left as u64));
+            }
+        }
+
+        deadlines.into_iter().min().map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some((message, Instant::now()));
+    }
+
+    pub fn get_message(&self) -> Option<&str> {
+        match &self.message {
+            Some((message, since)) if since.elapsed() < MESSAGE_TIME => Some(message.as_str()),
+            _ => None,
+        }
+    }
     
     pub fn set_current(&mut self, folder: Folder) {
         self.current.push(folder);
+        self.arrange_current();
         self.cursor.need_update = true;
 
         self.path_change = true;
@@ -112,20 +508,155 @@ impl<'a> Window<'a> {
     pub fn back_current(&mut self) {
         if self.current.len() > 1 {
             self.current.pop().unwrap();
+            if let Some(nested) = self.nested.last() {
+                if nested.depth == self.current.len() {
+                    self.nested.pop();
+                }
+            }
+            self.arrange_current();
 
             self.path_change = true;
             self.scroll_change = true;
+            self.scroll_x = 0;
         }
     }
 
-    pub fn plain_current(&self) -> String {
-        let mut plain = String::from("");
-        let mut s_flag = true;
-        for current in &self.current {
-            if s_flag {
-                s_flag = false;
+    // Goes to a folder of the innermost archive, absolute or relative to the current one
+    pub fn go_to(&mut self, path: &str) -> bool {
+        let base = match self.nested.last() {
+            Some(nested) => nested.depth + 1,
+            None => 1,
+        };
+        let mut current = self.current.clone();
+        if path.starts_with('/') {
+            current.truncate(base);
+        }
+
+        for name in path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            if name == ".." {
+                if current.len() > base {
+                    current.pop();
+                }
                 continue;
             }
+
+            let found = current[current.len() - 1].content.iter().find_map(|entry| match entry {
+                Entry::Folder(folder) if folder.name == name => Some(folder.clone()),
+                _ => None,
+            });
+            match found {
+                Some(folder) => current.push(folder),
+                None => return false,
+            }
+        }
+
+        self.current = current;
+        self.arrange_current();
+        self.cursor.y = 4;
+        self.cursor.need_update = true;
+        self.path_change = true;
+        self.scroll_change = true;
+        self.scroll_y = 0;
+        self.scroll_x = 0;
+        true
+    }
+
+    pub fn set_sort(&mut self, sort: SortOrder) {
+        self.sort = sort;
+        self.rearrange();
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = String::from(filter.trim());
+        self.rearrange();
+    }
+
+    fn rearrange(&mut self) {
+        self.arrange_current();
+        self.cursor.y = 4;
+        self.cursor.need_update = true;
+        self.scroll_change = true;
+        self.scroll_y = 0;
+        self.scroll_x = 0;
+    }
+
+    // Rebuilds the shown folder from the listing, leaving out what the filter hides and sorted as chosen
+    fn arrange_current(&mut self) {
+        let depth = self.current.len() - 1;
+        let mut folder = self.listed_folder(depth);
+        let filter = self.filter.to_lowercase();
+        folder.content.retain(|entry| Window::entry_name(entry).to_lowercase().contains(&filter));
+
+        // Folders go first by name, the biggest and the newest go first by size and date
+        match self.sort {
+            SortOrder::Archive => {},
+            SortOrder::Name => folder.content.sort_by_cached_key(|entry| {
+                (matches!(entry, Entry::File(_)), Window::entry_name(entry).to_lowercase())
+            }),
+            SortOrder::Size => folder.content.sort_by_cached_key(|entry| {
+                (Reverse(self.entry_size(entry)), Window::entry_name(entry).to_lowercase())
+            }),
+            SortOrder::Date => folder.content.sort_by_cached_key(|entry| {
+                (Reverse(self.entry_date(entry)), Window::entry_name(entry).to_lowercase())
+            }),
+        }
+        self.current[depth].content = folder.content;
+    }
+
+    // The folder at that depth as the archive lists it, found again from the root of its archive
+    fn listed_folder(&self, depth: usize) -> Folder {
+        let (base, mut folder) = match self.nested.iter().rev().find(|nested| nested.depth <= depth) {
+            Some(nested) => (nested.depth, &nested.root),
+            None => (0, &self.root),
+        };
+        for current in &self.current[base + 1..=depth] {
+            let found = folder.content.iter().find_map(|entry| match entry {
+                Entry::Folder(inner) if inner.name == current.name => Some(inner),
+                _ => None,
+            });
+            match found {
+                Some(inner) => folder = inner,
+                None => return self.current[depth].clone(),
+            }
+        }
+        folder.clone()
+    }
+
+    pub fn back_to(&mut self, depth: usize) {
+        while self.current.len() > depth.max(1) {
+            self.back_current();
+        }
+    }
+
+    pub fn crumbs(&self) -> Vec<Crumb> {
+        let mut names = vec![self.path.clone()];
+        names.extend(self.current.iter().skip(1).map(|folder| folder.name.clone()));
+        let room = usize::from(self.width.saturating_sub(2)).saturating_sub(text::width(&self.loading_label()));
+        breadcrumbs::layout(&names, room)
+    }
+
+    // Shown at the right of the header while the archive is still being listed
+    pub fn loading_label(&self) -> String {
+        match &self.loading {
+            Some(listing) => format!(" loading {} entries… ", listing.count),
+            None => String::new(),
+        }
+    }
+
+    pub fn plain_current(&self) -> String {
+        self.plain_from(0)
+    }
+
+    pub fn plain_member(&self) -> String {
+        match self.nested.last() {
+            Some(nested) => self.plain_from(nested.depth),
+            None => self.plain_from(0),
+        }
+    }
+
+    fn plain_from(&self, depth: usize) -> String {
+        let mut plain = String::from("");
+        for current in &self.current[depth + 1..] {
             plain += "/";
             plain += current.name.as_str();
         }
@@ -136,16 +667,13 @@ impl<'a> Window<'a> {
         self.path = path;
     }
 
-    pub fn get_writer(&self) -> &mut StdoutLock<'a> {
-        unsafe {
-            &mut (*self.writer)
+    pub fn get_path(&self) -> String {
+        match self.nested.last() {
+            Some(nested) => nested.path.clone(),
+            None => self.path.clone(),
         }
     }
 
-    pub fn get_path(&self) -> String {
-        self.path.clone()
-    } 
-
     pub fn move_up(&mut self) {
         if self.cursor.y > 4 {
             self.cursor.y -= 1;
@@ -160,7 +688,7 @@ impl<'a> Window<'a> {
         if self.cursor.y < self.height - 2 {
             self.cursor.y += 1;
             self.cursor.need_update = true;
-        } else {
+        } else if self.selected_index() + 1 < self.get_current().content.len() {
             self.scroll_y += 1;
             self.scroll_change = true;
         }
@@ -174,7 +702,7 @@ impl<'a> Window<'a> {
     }
 
     pub fn move_right(&mut self) {
-        if self.cursor.x < self.width - 2 {
+        if self.cursor.x < self.list_width() - 2 {
             self.cursor.x += 1;
             self.cursor.need_update = true;
         }
@@ -186,9 +714,30 @@ impl<'a> Window<'a> {
         self.cursor.need_update = true;
     }
 
+    pub fn list_width(&self) -> u16 {
+        if self.preview.enabled && self.width >= 40 {
+            self.width / 2
+        } else {
+            self.width
+        }
+    }
+
+    pub fn toggle_preview(&mut self) {
+        self.preview.enabled = !self.preview.enabled;
+        self.preview.clear();
+
+        if self.cursor.x > self.list_width() - 2 {
+            self.cursor.x = self.list_width() - 2;
+        }
+        self.scroll_x = self.scroll_x.min(self.max_scroll_x());
+        self.scroll_change = true;
+        self.cursor.need_update = true;
+    }
+
     pub fn set_size(&mut self, x: u16, y: u16) {
         self.width = x;
         self.height = y;
+        self.screen.resize(x, y);
     }
 
     pub fn set_scheme(&mut self, scheme: Scheme) {
@@ -0,0 +1,169 @@
+use crate::{files::{entry::EntryInfo, workspace}, zip_manager::safety};
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Opened files kept across sessions, one folder per key holding the file with its own name
+#[derive(Clone)]
+pub struct Cache {
+    pub dir: PathBuf,
+    // Bytes, the least recently used files go first when it is exceeded
+    pub limit: u64,
+}
+
+impl Cache {
+    // Under $XDG_CACHE_HOME/content-7z
+    pub fn open(limit: u64) -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::at(dir.join("content-7z"), limit))
+    }
+
+    pub fn at(dir: PathBuf, limit: u64) -> Self {
+        Self { dir, limit }
+    }
+
+    // A copy only counts when it is still whole, using it makes it the most recent
+    pub fn get(&self, key: &str, name: &str, size: u64) -> Option<PathBuf> {
+        let entry = self.dir.join(key);
+        let file = entry.join(name);
+        let metadata = fs::symlink_metadata(&file).ok()?;
+        if !metadata.is_file() || metadata.len() != size {
+            return None;
+        }
+        let _ = File::open(&entry).and_then(|entry| entry.set_modified(SystemTime::now()));
+        Some(file)
+    }
+
+    // Only regular files are kept, under the name they have in the archive
+    pub fn put(&self, key: &str, name: &str, file: &Path, progress: &dyn Fn(u64), cancelled: &AtomicBool) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(file)?;
+        if !metadata.is_file() || metadata.len() > self.limit {
+            return Ok(());
+        }
+
+        // Only the user can read what was extracted, like in the workspace
+        workspace::private_dir(&self.dir)?;
+        fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o700))?;
+
+        // Written aside and moved in place, a half copy is never found
+        let staging = self.dir.join(format!(".{}.{}", key, process::id()));
+        workspace::private_dir(&staging)?;
+        let stored = copy(file, &staging.join(name), progress, cancelled).and_then(|_| {
+            let entry = self.dir.join(key);
+            let _ = fs::remove_dir_all(&entry);
+            fs::rename(&staging, entry)
+        });
+        if stored.is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        stored?;
+        self.evict(key);
+        Ok(())
+    }
+
+    // Removes the least recently used entries until the cache fits, "keep" stays
+    pub fn evict(&self, keep: &str) {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, used)| *used);
+        for (entry, size, _) in entries {
+            if total <= self.limit {
+                break;
+            }
+            if entry.file_name().is_some_and(|name| name != keep) && fs::remove_dir_all(&entry).is_ok() {
+                total -= size;
+            }
+        }
+    }
+
+    // How many files and bytes went away
+    pub fn clear(&self) -> io::Result<(usize, u64)> {
+        let entries = self.entries();
+        let cleared = (entries.len(), entries.iter().map(|(_, size, _)| size).sum());
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(cleared),
+        }
+    }
+
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(read) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        read.filter_map(Result::ok)
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|entry| {
+                let used = entry.metadata().ok()?.modified().unwrap_or(UNIX_EPOCH);
+                let size = fs::read_dir(entry.path()).ok()?
+                    .filter_map(|file| file.ok()?.metadata().ok())
+                    .map(|metadata| metadata.len())
+                    .sum();
+                Some((entry.path(), size, used))
+            })
+            .collect()
+    }
+}
+
+// Copies in blocks, telling how many bytes are done, and stops as soon as it is cancelled
+pub fn copy(from: &Path, to: &Path, progress: &dyn Fn(u64), cancelled: &AtomicBool) -> io::Result<()> {
+    let copied = (|| {
+        let mut source = File::open(from)?;
+        let mut target = File::create(to)?;
+        target.set_permissions(source.metadata()?.permissions())?;
+        let mut buffer = vec![0; 1 << 20];
+        let mut done = 0;
+        loop {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "The copy was cancelled"));
+            }
+            let read = source.read(&mut buffer)?;
+            if read == 0 {
+                return Ok(());
+            }
+            target.write_all(&buffer[..read])?;
+            done += read as u64;
+            progress(done);
+        }
+    })();
+    if copied.is_err() {
+        let _ = fs::remove_file(to);
+    }
+    copied
+}
+
+// Changes when the archive file or the member does, None when the archive cannot be read
+pub fn key(archive: &Path, member: &str, info: &EntryInfo, crc: Option<&str>) -> Option<String> {
+    let metadata = fs::metadata(archive).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    let archive = fs::canonicalize(archive).ok()?;
+
+    let text = format!(
+        "{}\0{}\0{}.{}\0{}\0{}\0{}\0{}",
+        archive.display(), metadata.len(), modified.as_secs(), modified.subsec_nanos(),
+        member, info.size, info.date, crc.unwrap_or_default(),
+    );
+    Some(format!("{:016x}", fnv1a(text.as_bytes())))
+}
+
+// The key is kept on disk, so the hash cannot change between builds
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// From the technical listing of the member, not every format has one
+pub fn crc(archive: &str, member: &str) -> Option<String> {
+    let output = Command::new("7z").args(["l", "-slt", safety::LITERAL_NAMES, "--", archive, member]).output().ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    // Each member is a block of "Name = value" lines ended by an empty one
+    let mut block = output.lines()
+        .skip_while(|line| *line != format!("Path = {}", member))
+        .take_while(|line| !line.is_empty());
+    block.find_map(|line| line.strip_prefix("CRC = "))
+        .filter(|crc| !crc.is_empty())
+        .map(String::from)
+}
@@ -0,0 +1,163 @@
+use crate::{files::entry::EntryInfo, zip_manager::safety::{self, safe_join}};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+// What to do with a member that already exists where it is extracted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    Ask,
+    Overwrite,
+    Skip,
+    Rename,
+    KeepNewer,
+}
+
+pub const POLICIES: [(&str, Policy); 5] = [
+    ("ask", Policy::Ask),
+    ("overwrite", Policy::Overwrite),
+    ("skip", Policy::Skip),
+    ("rename", Policy::Rename),
+    ("keep-newer", Policy::KeepNewer),
+];
+
+impl Policy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        POLICIES.iter()
+            .find(|(policy_name, _)| *policy_name == name)
+            .map(|(_, policy)| *policy)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Choice {
+    Overwrite,
+    Skip,
+    Rename,
+}
+
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    // Path inside the archive, without the leading "/"
+    pub member: String,
+    pub path: PathBuf,
+    pub archived: EntryInfo,
+    pub size: u64,
+    pub date: String,
+}
+
+impl Conflict {
+    // None when the user has to be asked
+    pub fn choice(&self, policy: Policy) -> Option<Choice> {
+        match policy {
+            Policy::Ask => None,
+            Policy::Overwrite => Some(Choice::Overwrite),
+            Policy::Skip => Some(Choice::Skip),
+            Policy::Rename => Some(Choice::Rename),
+            // Both dates are local and sort as text, an unknown date never wins
+            Policy::KeepNewer if !self.archived.date.is_empty() && self.archived.date > self.date => Some(Choice::Overwrite),
+            Policy::KeepNewer => Some(Choice::Skip),
+        }
+    }
+}
+
+// The files of the archive that would replace an existing file in "dir"
+pub fn find(dir: &Path, members: Vec<(String, EntryInfo)>) -> Vec<Conflict> {
+    members.into_iter()
+        .filter_map(|(member, archived)| {
+            let path = safe_join(dir, &member)?;
+            let metadata = fs::metadata(&path).ok().filter(|metadata| metadata.is_file())?;
+            Some(Conflict {
+                member,
+                path,
+                archived,
+                size: metadata.len(),
+                date: metadata.modified().map(local_date).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+// The members resolved as skipped or renamed, everything else is overwritten
+#[derive(Clone, Debug, Default)]
+pub struct Resolution {
+    pub skip: Vec<String>,
+    pub rename: Vec<(String, PathBuf)>,
+}
+
+impl Resolution {
+    pub fn add(&mut self, conflict: &Conflict, choice: Choice) {
+        match choice {
+            Choice::Overwrite => {},
+            Choice::Skip => self.skip.push(conflict.member.clone()),
+            Choice::Rename => self.rename.push((conflict.member.clone(), renamed(&conflict.path))),
+        }
+    }
+
+    pub fn is_resolved(&self, member: &str) -> bool {
+        self.skip.iter().any(|skip| skip == member) || self.rename.iter().any(|(rename, _)| rename == member)
+    }
+
+    // 7z arguments of the main extraction, the renamed members are left for a second one
+    pub fn args<S: AsRef<str>>(&self, archive: &str, dir: &str, members: &[String], blocked: &[S]) -> Vec<String> {
+        let mut switches = vec![String::from("x"), String::from("-aoa"), format!("-o{}", dir)];
+        switches.extend(safety::exclusions(&self.skip));
+        switches.extend(self.rename.iter().map(|(member, _)| format!("-x!{}", member)));
+        switches.extend(safety::exclusions(blocked));
+        safety::command_args(switches, archive, members)
+    }
+
+    // 7z names the new copies the same way as renamed()
+    pub fn rename_args<S: AsRef<str>>(&self, archive: &str, dir: &str, blocked: &[S]) -> Option<Vec<String>> {
+        if self.rename.is_empty() {
+            return None;
+        }
+        let mut switches = vec![String::from("x"), String::from("-aou"), format!("-o{}", dir)];
+        switches.extend(safety::exclusions(blocked));
+        let members: Vec<String> = self.rename.iter().map(|(member, _)| member.clone()).collect();
+        Some(safety::command_args(switches, archive, &members))
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = vec![];
+        if !self.skip.is_empty() {
+            parts.push(format!("{} skipped", self.skip.len()));
+        }
+        if !self.rename.is_empty() {
+            parts.push(format!("{} renamed", self.rename.len()));
+        }
+        parts.join(", ")
+    }
+}
+
+// The first free "name_N.ext" next to the file
+pub fn renamed(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name.as_ref(), String::new()),
+    };
+
+    (1..)
+        .map(|number| path.with_file_name(format!("{}_{}{}", stem, number, extension)))
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+// Same format as the dates of the listing: "2024-06-01 12:00"
+pub fn local_date(time: SystemTime) -> String {
+    let Ok(since) = time.duration_since(SystemTime::UNIX_EPOCH) else {
+        return String::new();
+    };
+    let seconds = since.as_secs() as libc::time_t;
+    let mut date: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&seconds, &mut date) }.is_null() {
+        return String::new();
+    }
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        date.tm_year + 1900, date.tm_mon + 1, date.tm_mday, date.tm_hour, date.tm_min
+    )
+}
@@ -0,0 +1,115 @@
+use crate::{
+    files::entry::{EntryInfo, EntryType},
+    zip_manager::manager::{parse_entry, LISTING_HEADER},
+};
+use std::{
+    io::{BufRead, BufReader, Read},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+const BATCH: usize = 1000;
+const BATCH_TIME: Duration = Duration::from_millis(50);
+
+pub type ListedEntry = (String, EntryType, EntryInfo);
+
+enum Message {
+    Entries(Vec<ListedEntry>),
+    Failed(String),
+}
+
+// The listing of an archive read in the background, entries arrive in batches
+pub struct Listing {
+    pub count: usize,
+    child: Child,
+    receiver: Receiver<Message>,
+}
+
+impl Drop for Listing {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Listing {
+    pub fn start(path: &str) -> Result<Self, String> {
+        let mut child = Command::new("7z")
+            .args(["l", "--", path])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("Cannot execute the extractor: {}", err))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let stdout = child.stdout.take().unwrap();
+        let mut stderr = child.stderr.take().unwrap();
+        thread::spawn(move || {
+            // The header is two lines, the second one is what marks the start of the entries
+            let start = LISTING_HEADER.lines().last().unwrap();
+            let mut lines = BufReader::new(stdout).lines().map_while(Result::ok);
+            lines.by_ref().find(|line| line == start);
+
+            // Slow listings still show their entries as they come
+            let mut batch = vec![];
+            let mut sent = Instant::now();
+            for line in lines.by_ref() {
+                let Some(entry) = parse_entry(&line) else {
+                    break;
+                };
+                batch.push(entry);
+                if batch.len() >= BATCH || sent.elapsed() >= BATCH_TIME {
+                    if sender.send(Message::Entries(std::mem::take(&mut batch))).is_err() {
+                        return;
+                    }
+                    sent = Instant::now();
+                }
+            }
+            let _ = sender.send(Message::Entries(batch));
+            lines.for_each(drop);
+
+            let mut errors = String::new();
+            let _ = stderr.read_to_string(&mut errors);
+            if !errors.trim().is_empty() {
+                let _ = sender.send(Message::Failed(errors));
+            }
+        });
+
+        Ok(Self {
+            count: 0,
+            child,
+            receiver,
+        })
+    }
+
+    // The entries read since the last call, and once the listing is over whether it went well
+    pub fn update(&mut self) -> (Vec<ListedEntry>, Option<Result<(), String>>) {
+        let mut entries = vec![];
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Entries(batch)) => entries.extend(batch),
+                Ok(Message::Failed(errors)) => return self.finish(entries, Err(errors)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let status = self.child.wait().map_err(|err| err.to_string());
+                    let result = match status {
+                        Ok(status) if status.success() => Ok(()),
+                        Ok(status) => Err(format!("The extractor ended with {}", status)),
+                        Err(err) => Err(err),
+                    };
+                    return self.finish(entries, result);
+                },
+            }
+        }
+        self.count += entries.len();
+        (entries, None)
+    }
+
+    fn finish(&mut self, entries: Vec<ListedEntry>, result: Result<(), String>) -> (Vec<ListedEntry>, Option<Result<(), String>>) {
+        self.count += entries.len();
+        (entries, Some(result))
+    }
+}
@@ -1,9 +1,36 @@
-use std::process::Command;
+use std::{collections::HashMap, process::Command};
 use crate::files::{
     folder::Folder,
-    entry::EntryType
+    entry::{EntryInfo, EntryType}
 };
 
+const ARCHIVE_EXTENSIONS: [&str; 22] = [
+    "7z", "zip", "rar", "tar", "gz", "tgz", "bz2", "tbz", "tbz2", "xz", "txz", "lzma",
+    "zst", "cab", "iso", "wim", "jar", "war", "ear", "apk", "cpio", "rpm",
+];
+
+pub const LISTING_HEADER: &str = "   Date      Time    Attr         Size   Compressed  Name\n------------------- ----- ------------ ------------  ------------------------\n";
+
+// One member of the listing, None for the line that closes it
+pub fn parse_entry(line: &str) -> Option<(String, EntryType, EntryInfo)> {
+    if line.len() < 53 || line.get(20..25) == Some("-----") {
+        return None;
+    }
+
+    let entry_type = match line.get(20..25) {
+        Some("D....") => EntryType::Folder,
+        _ => EntryType::File,
+    };
+    let number = |range: std::ops::Range<usize>| line.get(range).and_then(|text| text.trim().parse::<u64>().ok());
+    let info = EntryInfo {
+        size: number(26..38).unwrap_or(0),
+        compressed: number(39..51),
+        date: line.get(0..16).unwrap_or_default().trim().to_string(),
+        folder: matches!(entry_type, EntryType::Folder),
+    };
+    Some((String::from(line.get(53..)?), entry_type, info))
+}
+
 pub struct ZipManager {
     pub output: String,
     pub err: String,
@@ -13,7 +40,7 @@ pub struct ZipManager {
 impl ZipManager {
     pub fn process(file_name: &str) -> Self {
         let res = Command::new("7z")
-            .args(vec!["l", file_name])
+            .args(vec!["l", "--", file_name])
             .output();
         match res {
             Err(_) => Self {
@@ -34,25 +61,43 @@ impl ZipManager {
         }
     }
 
+    pub fn is_archive(file_name: &str) -> bool {
+        match file_name.rsplit_once('.') {
+            Some((name, extension)) if !name.is_empty() => {
+                ARCHIVE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+            },
+            _ => false,
+        }
+    }
+
     pub fn get_root(&self) -> Folder {
         let mut root = Folder::new(".");
+        for (name, entry_type, _) in self.entries() {
+            root.add_entry(&name, &entry_type);
+        }
+        root
+    }
 
-        let start_point: usize = self.output.find("   Date      Time    Attr         Size   Compressed  Name\n------------------- ----- ------------ ------------  ------------------------\n").expect("The content isn't be found") + "   Date      Time    Attr         Size   Compressed  Name\n------------------- ----- ------------ ------------  ------------------------\n".len();
-        let clean_output = &self.output[start_point..];
-        let lines: Vec<&str> = clean_output.split("\n").collect();
+    // Size, compressed size and date of every member, by its path in the archive
+    pub fn get_info(&self) -> HashMap<String, EntryInfo> {
+        self.entries().into_iter().map(|(name, _, info)| (name, info)).collect()
+    }
 
-        for line in lines {
-            if &line[20..25].to_string() == "-----" {
-                break;
-            }
-            if &line[20..25].to_string() == "D...." {
-                root.add_entry(&line[53..].to_string(), &EntryType::Folder);
-            } else {
-                root.add_entry(&line[53..].to_string(), &EntryType::File);
-            }
-        }
+    // Files of the archive with their info, folders left out
+    pub fn files(&self) -> Vec<(String, EntryInfo)> {
+        self.entries().into_iter()
+            .filter(|(_, entry_type, _)| matches!(entry_type, EntryType::File))
+            .map(|(name, _, info)| (name, info))
+            .collect()
+    }
 
-        root
+    fn entries(&self) -> Vec<(String, EntryType, EntryInfo)> {
+        let Some(start_point) = self.output.find(LISTING_HEADER) else {
+            return vec![];
+        };
+        self.output[start_point + LISTING_HEADER.len()..].split('\n')
+            .map_while(parse_entry)
+            .collect()
     }
 
     pub fn get_path(&self) -> String {
@@ -0,0 +1,190 @@
+use crate::zip_manager::safety;
+use std::{
+    io::Read,
+    os::unix::process::CommandExt,
+    process::{Child, ChildStdout, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+// Chunks read ahead of what is shown, the command waits once they are taken
+const PIPE_CHUNKS: usize = 16;
+const PIPE_CHUNK: usize = 64 * 1024;
+
+pub struct MemberReader {
+    child: Child,
+    stdout: Option<ChildStdout>,
+    pub content: Vec<u8>,
+}
+
+impl Drop for MemberReader {
+    fn drop(&mut self) {
+        // The extractor may still be writing, nobody is going to read it anymore
+        self.stdout.take();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl MemberReader {
+    pub fn open(archive: &str, member: &str) -> Result<Self, String> {
+        let mut child = Command::new("7z")
+            .args(["e", "-so", safety::LITERAL_NAMES, "--", archive, member])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("Cannot execute the extractor: {}", err))?;
+
+        let stdout = child.stdout.take();
+        Ok(Self {
+            child,
+            stdout,
+            content: Vec::new(),
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.stdout.is_none()
+    }
+
+    pub fn fill_to(&mut self, size: usize) -> usize {
+        let mut buffer = [0; 8192];
+        while self.content.len() < size {
+            let Some(stdout) = self.stdout.as_mut() else {
+                break;
+            };
+
+            match stdout.read(&mut buffer) {
+                Ok(0) | Err(_) => {
+                    self.stdout.take();
+                    let _ = self.child.wait();
+                },
+                Ok(read) => self.content.extend_from_slice(&buffer[..read]),
+            }
+        }
+        self.content.len()
+    }
+
+    pub fn fill_all(&mut self) -> usize {
+        self.fill_to(usize::MAX)
+    }
+
+    // The first "size" bytes arrive later, the extractor is stopped if they are not wanted anymore
+    pub fn read_in_background(mut self, size: usize) -> BackgroundRead {
+        let (sender, receiver) = mpsc::channel();
+        let stdout = self.stdout.take();
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            if let Some(stdout) = stdout {
+                let _ = stdout.take(size as u64).read_to_end(&mut bytes);
+            }
+            let _ = sender.send(bytes);
+        });
+
+        BackgroundRead {
+            _reader: self,
+            receiver,
+        }
+    }
+
+    // The member goes straight from the extractor to the command, which runs in the background
+    pub fn pipe_into(mut self, mut command: Command) -> Result<PipedCommand, String> {
+        let Some(stdout) = self.stdout.take() else {
+            return Err(String::from("The member was already read."));
+        };
+
+        command.stdin(Stdio::from(stdout))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Without a terminal of its own, a command that wants one fails instead of fighting over the keyboard
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+        let mut child = command.spawn().map_err(|err| format!("Cannot execute the command: {}", err))?;
+
+        // Both outputs end up together, in the order they are read
+        let (sender, receiver) = mpsc::sync_channel(PIPE_CHUNKS);
+        let outputs: [Box<dyn Read + Send>; 2] = [Box::new(child.stdout.take().unwrap()), Box::new(child.stderr.take().unwrap())];
+        for mut output in outputs {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let mut buffer = vec![0; PIPE_CHUNK];
+                while let Ok(read) = output.read(&mut buffer) {
+                    if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Ok(PipedCommand {
+            _reader: self,
+            child,
+            receiver,
+            content: Vec::new(),
+            status: None,
+        })
+    }
+}
+
+pub struct BackgroundRead {
+    _reader: MemberReader,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl BackgroundRead {
+    // Never waits, the bytes are only there once they were all read
+    pub fn take(&self) -> Option<Vec<u8>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+// A command reading a member, what it prints is only taken as far as it is wanted
+pub struct PipedCommand {
+    _reader: MemberReader,
+    child: Child,
+    receiver: Receiver<Vec<u8>>,
+    pub content: Vec<u8>,
+    pub status: Option<ExitStatus>,
+}
+
+impl Drop for PipedCommand {
+    fn drop(&mut self) {
+        // Once waited on, the id may already belong to someone else
+        if self.status.is_some() {
+            return;
+        }
+        // The shell leads its own group, what it started goes away with it
+        unsafe {
+            libc::kill(-(self.child.id() as i32), libc::SIGKILL);
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl PipedCommand {
+    pub fn is_finished(&self) -> bool {
+        self.status.is_some()
+    }
+
+    // Never waits, only takes what the command already printed
+    pub fn fill_to(&mut self, size: usize) -> usize {
+        while self.content.len() < size && self.status.is_none() {
+            match self.receiver.try_recv() {
+                Ok(bytes) => self.content.extend(bytes),
+                Err(TryRecvError::Empty) => break,
+                // The outputs can close before the command ends
+                Err(TryRecvError::Disconnected) => match self.child.try_wait() {
+                    Ok(None) => break,
+                    status => self.status = Some(status.ok().flatten().unwrap_or_default()),
+                },
+            }
+        }
+        self.content.len()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::files::entry::EntryInfo;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+// Why a member cannot be extracted where it says
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hazard {
+    Absolute,
+    Escapes,
+    // A file member used as a folder is a symbolic link, writing under it lands wherever it points
+    ThroughLink,
+}
+
+impl Hazard {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Hazard::Absolute => "absolute path",
+            Hazard::Escapes => "leaves the destination",
+            Hazard::ThroughLink => "inside a link",
+        }
+    }
+}
+
+// What to do with the symbolic links an extraction creates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinkPolicy {
+    Skip,
+    Preserve,
+    FollowWithinRoot,
+}
+
+impl LinkPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(LinkPolicy::Skip),
+            "preserve" => Some(LinkPolicy::Preserve),
+            "follow-within-root" => Some(LinkPolicy::FollowWithinRoot),
+            _ => None,
+        }
+    }
+}
+
+// Only looks at the path, see hazard() for the links
+pub fn path_hazard(member: &str) -> Option<Hazard> {
+    if member.starts_with(['/', '\\']) || member.get(1..2) == Some(":") {
+        return Some(Hazard::Absolute);
+    }
+
+    let mut depth = 0usize;
+    for part in member.split(['/', '\\']) {
+        match part {
+            "" | "." => {},
+            ".." => match depth.checked_sub(1) {
+                Some(up) => depth = up,
+                None => return Some(Hazard::Escapes),
+            },
+            _ => depth += 1,
+        }
+    }
+    None
+}
+
+pub fn hazard(info: &HashMap<String, EntryInfo>, member: &str) -> Option<Hazard> {
+    if let Some(hazard) = path_hazard(member) {
+        return Some(hazard);
+    }
+    member.match_indices('/')
+        .any(|(index, _)| info.get(&member[..index]).is_some_and(|parent| !parent.folder))
+        .then_some(Hazard::ThroughLink)
+}
+
+// Where the member lands inside root, None when it would end up somewhere else
+pub fn safe_join(root: &Path, member: &str) -> Option<PathBuf> {
+    if path_hazard(member).is_some() {
+        return None;
+    }
+
+    let mut path = root.to_path_buf();
+    for part in member.split(['/', '\\']) {
+        match part {
+            "" | "." => {},
+            ".." => {
+                path.pop();
+            },
+            _ => path.push(part),
+        }
+    }
+    Some(path)
+}
+
+// 7z reads switches anywhere on the line, so the names only come after "--",
+// and "-spd" keeps a '*' or '?' in them (or in an exclusion) from matching other entries
+pub fn command_args(mut switches: Vec<String>, archive: &str, members: &[String]) -> Vec<String> {
+    switches.push(String::from(LITERAL_NAMES));
+    switches.push(String::from("--"));
+    switches.push(String::from(archive));
+    switches.extend(members.iter().cloned());
+    switches
+}
+
+pub const LITERAL_NAMES: &str = "-spd";
+
+pub fn exclusions<S: AsRef<str>>(names: &[S]) -> impl Iterator<Item = String> + '_ {
+    names.iter().map(|name| format!("-x!{}", name.as_ref()))
+}
+
+// Removes the links the policy does not allow, returns how many went away
+pub fn settle_links(root: &Path, paths: &[PathBuf], policy: LinkPolicy) -> usize {
+    if policy == LinkPolicy::Preserve {
+        return 0;
+    }
+
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    paths.iter()
+        .filter(|path| fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()))
+        .filter(|path| policy == LinkPolicy::Skip || !points_within(&root, path))
+        .filter(|path| fs::remove_file(path).is_ok())
+        .count()
+}
+
+fn points_within(root: &Path, link: &Path) -> bool {
+    // Links to links are followed to the end, a dangling one is only judged by its text
+    if let Ok(target) = fs::canonicalize(link) {
+        return target.starts_with(root);
+    }
+    let Ok(target) = fs::read_link(link) else {
+        return false;
+    };
+
+    let mut path = match link.parent() {
+        Some(parent) => fs::canonicalize(parent).unwrap_or_else(|_| parent.to_path_buf()),
+        None => PathBuf::new(),
+    };
+    for component in target.components() {
+        match component {
+            Component::RootDir | Component::Prefix(_) => path = PathBuf::from(component.as_os_str()),
+            Component::ParentDir => {
+                path.pop();
+            },
+            Component::CurDir => {},
+            Component::Normal(part) => path.push(part),
+        }
+    }
+    path.starts_with(root)
+}
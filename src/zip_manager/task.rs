@@ -0,0 +1,250 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+pub struct Progress {
+    pub percent: u8,
+    pub files: Option<usize>,
+    pub current: String,
+}
+
+impl Progress {
+    // For work measured in bytes, like a copy
+    pub fn bytes(done: u64, total: u64, current: &str) -> Self {
+        Self {
+            percent: (done * 100 / total.max(1)).min(100) as u8,
+            files: None,
+            current: String::from(current),
+        }
+    }
+}
+
+enum Work {
+    // A 7z command, its errors are read from its output
+    Extractor(Child, Option<JoinHandle<String>>),
+    // Done by the program itself, told to stop when it is cancelled
+    Thread(Option<JoinHandle<Result<(), String>>>, Arc<AtomicBool>),
+}
+
+// Work running in the background, a 7z command or a thread, with its progress
+pub struct Task {
+    pub title: String,
+    pub total_bytes: u64,
+    pub total_files: usize,
+    pub progress: Progress,
+    pub started: Instant,
+    // Created by the task, removed when it is cancelled or fails
+    pub partial: Vec<PathBuf>,
+    // Folders made for the task, removed when it stops them empty
+    pub made_dirs: Vec<PathBuf>,
+    work: Work,
+    receiver: Receiver<Progress>,
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Folders above the path that do not exist yet
+pub fn missing_dirs(path: &Path) -> Vec<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.as_os_str().is_empty() && fs::symlink_metadata(dir).is_err())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+// 7z rewrites the progress line with backspaces: " 45% 12 - dir/file.txt"
+pub fn parse_progress(line: &str) -> Option<Progress> {
+    let line = line.trim();
+    let (percent, rest) = line.split_once('%')?;
+    let percent = percent.trim().parse::<u8>().ok()?.min(100);
+
+    let mut words = rest.split_whitespace().peekable();
+    let files = words.peek().and_then(|word| word.parse().ok());
+    if files.is_some() {
+        words.next();
+    }
+    // A single character tells the operation done with the file
+    if words.peek().is_some_and(|word| word.chars().count() == 1) {
+        words.next();
+    }
+
+    Some(Progress {
+        percent,
+        files,
+        current: words.collect::<Vec<_>>().join(" "),
+    })
+}
+
+impl Task {
+    pub fn start(title: &str, args: &[&str]) -> Result<Self, String> {
+        let mut child = Command::new("7z")
+            .args(&args[..1])
+            .args(["-bsp1", "-bso0"])
+            .args(&args[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("Cannot execute the extractor: {}", err))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut stdout = child.stdout.take().unwrap();
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            let mut line = vec![];
+            while let Ok(read) = stdout.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                for byte in &buffer[..read] {
+                    if matches!(byte, b'\x08' | b'\r' | b'\n') {
+                        if let Some(progress) = parse_progress(&String::from_utf8_lossy(&line)) {
+                            if sender.send(progress).is_err() {
+                                return;
+                            }
+                        }
+                        line.clear();
+                    } else {
+                        line.push(*byte);
+                    }
+                }
+            }
+        });
+
+        let mut stderr = child.stderr.take().unwrap();
+        let errors = thread::spawn(move || {
+            let mut errors = String::new();
+            let _ = stderr.read_to_string(&mut errors);
+            errors
+        });
+
+        Ok(Self::new(title, Work::Extractor(child, Some(errors)), receiver))
+    }
+
+    // The work sends its progress and should give up soon once it is told it was cancelled
+    pub fn spawn<F>(title: &str, work: F) -> Self
+    where
+        F: FnOnce(&Sender<Progress>, &AtomicBool) -> Result<(), String> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        let worker = thread::spawn(move || work(&sender, &flag));
+        Self::new(title, Work::Thread(Some(worker), cancelled), receiver)
+    }
+
+    fn new(title: &str, work: Work, receiver: Receiver<Progress>) -> Self {
+        Self {
+            title: String::from(title),
+            total_bytes: 0,
+            total_files: 0,
+            progress: Progress { percent: 0, files: None, current: String::new() },
+            started: Instant::now(),
+            partial: vec![],
+            made_dirs: vec![],
+            work,
+            receiver,
+        }
+    }
+
+    // Returns how the task ended once it is over
+    pub fn update(&mut self) -> Option<Result<(), String>> {
+        while let Ok(progress) = self.receiver.try_recv() {
+            self.progress = progress;
+        }
+
+        let result = match &mut self.work {
+            Work::Extractor(child, errors) => {
+                let status = child.try_wait().ok()??;
+                let errors = errors.take().and_then(|errors| errors.join().ok()).unwrap_or_default();
+                let errors = errors.trim();
+                match status.success() {
+                    true => Ok(()),
+                    false if errors.is_empty() => Err(format!("The extractor ended with {}", status)),
+                    false => Err(String::from(errors)),
+                }
+            },
+            Work::Thread(worker, _) => {
+                if !worker.as_ref()?.is_finished() {
+                    return None;
+                }
+                worker.take()?.join().unwrap_or_else(|_| Err(String::from("The task stopped unexpectedly")))
+            },
+        };
+
+        if result.is_err() {
+            self.remove_partial();
+        }
+        Some(result)
+    }
+
+    pub fn cancel(&mut self) {
+        self.stop();
+        self.remove_partial();
+    }
+
+    // A thread cannot be killed, it is left to notice it was cancelled
+    fn stop(&mut self) {
+        match &mut self.work {
+            Work::Extractor(child, _) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            },
+            Work::Thread(worker, cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                worker.take();
+            },
+        }
+    }
+
+    // Also records the folders above them that the task will have to make
+    pub fn set_partial(&mut self, paths: Vec<PathBuf>) {
+        for path in &paths {
+            self.made_dirs.extend(missing_dirs(path));
+        }
+        self.partial = paths;
+    }
+
+    fn remove_partial(&self) {
+        for path in &self.partial {
+            if path.is_dir() {
+                let _ = fs::remove_dir_all(path);
+            } else {
+                let _ = fs::remove_file(path);
+            }
+        }
+        // The deepest first, a folder that still holds something stays
+        let mut dirs = self.made_dirs.clone();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        dirs.dedup();
+        for dir in dirs {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    pub fn bytes_done(&self) -> u64 {
+        self.total_bytes * u64::from(self.progress.percent) / 100
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let percent = u32::from(self.progress.percent);
+        if percent == 0 {
+            return None;
+        }
+        Some(self.started.elapsed() * (100 - percent) / percent)
+    }
+}
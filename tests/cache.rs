@@ -0,0 +1,85 @@
+use content_7z::{files::entry::EntryInfo, zip_manager::cache::{copy, key, Cache}};
+use std::{cell::Cell, env, fs, os::unix::fs::PermissionsExt, sync::atomic::AtomicBool, thread, time::Duration};
+
+#[test]
+fn keys_change_with_the_member() {
+    let dir = env::temp_dir().join(format!("content-7z-cache-key-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let archive = dir.join("any.7z");
+    fs::write(&archive, "archive").unwrap();
+
+    let info = EntryInfo { size: 5, date: String::from("2024-06-01 12:00"), ..EntryInfo::default() };
+    let first = key(&archive, "docs/a.txt", &info, Some("CAFEBABE")).unwrap();
+    assert_eq!(first, key(&archive, "docs/a.txt", &info, Some("CAFEBABE")).unwrap());
+    assert_ne!(first, key(&archive, "docs/a.txt", &info, Some("DEADBEEF")).unwrap());
+    assert_ne!(first, key(&archive, "docs/b.txt", &info, Some("CAFEBABE")).unwrap());
+    assert!(key(&dir.join("missing.7z"), "docs/a.txt", &info, None).is_none());
+
+    fs::write(&archive, "archive, changed").unwrap();
+    assert_ne!(first, key(&archive, "docs/a.txt", &info, Some("CAFEBABE")).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn evicts_the_least_recently_used() {
+    let dir = env::temp_dir().join(format!("content-7z-cache-{}", std::process::id()));
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    for name in ["a", "b", "c"] {
+        fs::write(source.join(name), "12345").unwrap();
+    }
+
+    let cache = Cache::at(dir.join("cache"), 12);
+    cache.put("first", "a.txt", &source.join("a"), &|_| {}, &AtomicBool::new(false)).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    cache.put("second", "b.txt", &source.join("b"), &|_| {}, &AtomicBool::new(false)).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    assert!(cache.get("first", "a.txt", 5).is_some());
+    assert!(cache.get("first", "a.txt", 6).is_none());
+    thread::sleep(Duration::from_millis(20));
+    cache.put("third", "c.txt", &source.join("c"), &|_| {}, &AtomicBool::new(false)).unwrap();
+
+    assert!(cache.get("first", "a.txt", 5).is_some());
+    assert!(cache.get("second", "b.txt", 5).is_none());
+    assert!(cache.get("third", "c.txt", 5).is_some());
+    assert_eq!(cache.clear().unwrap(), (2, 10));
+    assert!(!cache.dir.exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn copies_report_progress_and_stop_when_cancelled() {
+    let dir = env::temp_dir().join(format!("content-7z-cache-copy-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("source");
+    fs::write(&source, vec![7; 3 << 20]).unwrap();
+
+    let done = Cell::new(0);
+    copy(&source, &dir.join("copy"), &|bytes| done.set(bytes), &AtomicBool::new(false)).unwrap();
+    assert_eq!(done.get(), 3 << 20);
+    assert_eq!(fs::read(dir.join("copy")).unwrap(), fs::read(&source).unwrap());
+
+    // Nothing half copied is left behind
+    assert!(copy(&source, &dir.join("cancelled"), &|_| {}, &AtomicBool::new(true)).is_err());
+    assert!(!dir.join("cancelled").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn only_the_user_can_read_the_cache() {
+    let dir = env::temp_dir().join(format!("content-7z-cache-private-{}", std::process::id()));
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a"), "12345").unwrap();
+
+    // A cache left open by an older version is closed too
+    let cache = Cache::at(dir.join("cache"), 12);
+    fs::create_dir_all(&cache.dir).unwrap();
+    fs::set_permissions(&cache.dir, fs::Permissions::from_mode(0o755)).unwrap();
+    cache.put("first", "a.txt", &source.join("a"), &|_| {}, &AtomicBool::new(false)).unwrap();
+
+    for private in [cache.dir.clone(), cache.dir.join("first")] {
+        assert_eq!(fs::metadata(&private).unwrap().permissions().mode() & 0o777, 0o700);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
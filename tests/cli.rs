@@ -0,0 +1,44 @@
+use content_7z::{cli::{parse, Args}, zip_manager::conflict::Policy};
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn parses_the_browser_arguments() {
+    assert_eq!(parse(&args("any.7z")).unwrap(), Args { archive: String::from("any.7z"), ..Args::default() });
+
+    let parsed = parse(&args("--keep-newer any.7z")).unwrap();
+    assert_eq!(parsed.policy, Some(Policy::KeepNewer));
+    assert_eq!(parsed.extract, None);
+}
+
+#[test]
+fn parses_the_extraction_arguments() {
+    let parsed = parse(&args("--extract out any.7z docs/a.txt lib --skip")).unwrap();
+    assert_eq!(parsed.extract.as_deref(), Some("out"));
+    assert_eq!(parsed.archive, "any.7z");
+    assert_eq!(parsed.members, ["docs/a.txt", "lib"]);
+    assert_eq!(parsed.policy, Some(Policy::Skip));
+
+    let parsed = parse(&args("--extract=out -- -odd.7z")).unwrap();
+    assert_eq!(parsed.extract.as_deref(), Some("out"));
+    assert_eq!(parsed.archive, "-odd.7z");
+}
+
+#[test]
+fn rejects_wrong_arguments() {
+    assert!(parse(&args("")).is_err());
+    assert!(parse(&args("--overwrite --rename any.7z")).is_err());
+    assert!(parse(&args("any.7z docs")).is_err());
+    assert!(parse(&args("-x")).is_err());
+    assert!(parse(&args("--force any.7z")).is_err());
+}
+
+#[test]
+fn parses_the_cache_command() {
+    assert!(parse(&args("cache clear")).unwrap().clear_cache);
+    assert!(parse(&args("cache")).is_err());
+    assert!(parse(&args("cache clear any.7z")).is_err());
+    assert!(!parse(&args("./cache")).unwrap().clear_cache);
+}
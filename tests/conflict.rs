@@ -0,0 +1,104 @@
+use content_7z::{
+    files::entry::EntryInfo,
+    zip_manager::conflict::{find, renamed, Choice, Conflict, Policy, Resolution},
+};
+use std::{env, fs, path::PathBuf};
+
+fn conflict(archived: &str, existing: &str) -> Conflict {
+    Conflict {
+        member: String::from("docs/a.txt"),
+        path: PathBuf::from("out/docs/a.txt"),
+        archived: EntryInfo { size: 10, date: String::from(archived), ..EntryInfo::default() },
+        size: 5,
+        date: String::from(existing),
+    }
+}
+
+#[test]
+fn policies_choose_for_every_conflict() {
+    let found = conflict("2024-06-01 12:00", "2024-05-01 09:30");
+    assert_eq!(found.choice(Policy::Ask), None);
+    assert_eq!(found.choice(Policy::Overwrite), Some(Choice::Overwrite));
+    assert_eq!(found.choice(Policy::Skip), Some(Choice::Skip));
+    assert_eq!(found.choice(Policy::Rename), Some(Choice::Rename));
+    assert_eq!(found.choice(Policy::KeepNewer), Some(Choice::Overwrite));
+
+    assert_eq!(conflict("2024-06-01 12:00", "2024-06-01 12:00").choice(Policy::KeepNewer), Some(Choice::Skip));
+    assert_eq!(conflict("", "2024-06-01 12:00").choice(Policy::KeepNewer), Some(Choice::Skip));
+    assert_eq!(Policy::from_name("keep-newer"), Some(Policy::KeepNewer));
+    assert_eq!(Policy::from_name("newest"), None);
+}
+
+#[test]
+fn resolution_builds_the_extractor_arguments() {
+    let mut resolution = Resolution::default();
+    let skipped = conflict("", "");
+    let mut kept = conflict("", "");
+    kept.member = String::from("docs/b.txt");
+    resolution.add(&skipped, Choice::Skip);
+    resolution.add(&kept, Choice::Rename);
+    resolution.add(&kept, Choice::Overwrite);
+
+    assert_eq!(
+        resolution.args("any.7z", "out", &[String::from("docs")], &["docs/c.txt"]),
+        ["x", "-aoa", "-oout", "-x!docs/a.txt", "-x!docs/b.txt", "-x!docs/c.txt", "-spd", "--", "any.7z", "docs"],
+    );
+    assert_eq!(resolution.rename_args("any.7z", "out", &["docs/c.txt"]).unwrap(), ["x", "-aou", "-oout", "-x!docs/c.txt", "-spd", "--", "any.7z", "docs/b.txt"]);
+    assert!(resolution.is_resolved("docs/a.txt"));
+    assert!(!resolution.is_resolved("docs/c.txt"));
+    assert_eq!(resolution.summary(), "1 skipped, 1 renamed");
+    assert!(Resolution::default().rename_args::<&str>("any.7z", "out", &[]).is_none());
+}
+
+#[test]
+fn finds_existing_files_and_free_names() {
+    let dir = env::temp_dir().join(format!("content-7z-conflict-{}", std::process::id()));
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    fs::write(dir.join("docs/a.txt"), "hello").unwrap();
+    fs::write(dir.join("docs/a_1.txt"), "").unwrap();
+    fs::write(dir.join("README"), "").unwrap();
+
+    let members = ["docs/a.txt", "docs/b.txt", "docs"].map(|name| (String::from(name), EntryInfo::default()));
+    let found = find(&dir, members.to_vec());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].member, "docs/a.txt");
+    assert_eq!(found[0].size, 5);
+    assert_eq!(found[0].date.len(), "2024-06-01 12:00".len());
+
+    assert_eq!(renamed(&dir.join("docs/a.txt")), dir.join("docs/a_2.txt"));
+    assert_eq!(renamed(&dir.join("README")), dir.join("README_1"));
+    assert_eq!(renamed(&dir.join(".hidden")), dir.join(".hidden_1"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn names_never_reach_7z_as_switches() {
+    let mut resolution = Resolution::default();
+    let mut renamed = conflict("", "");
+    renamed.member = String::from("-x!b.txt");
+    resolution.add(&renamed, Choice::Rename);
+
+    let members = [String::from("-o/home/user"), String::from("-i!*")];
+    let args = resolution.args("-any.7z", "out", &members, &["-bad"]);
+    let names = args.iter().position(|arg| arg == "--").unwrap();
+    assert!(args[..names].iter().all(|arg| arg == "x" || arg.starts_with('-')));
+    assert_eq!(args[names + 1..], ["-any.7z", "-o/home/user", "-i!*"]);
+
+    let args = resolution.rename_args("-any.7z", "out", &["-bad"]).unwrap();
+    assert_eq!(args[args.len() - 3..], ["--", "-any.7z", "-x!b.txt"]);
+}
+
+#[test]
+fn wildcards_in_names_only_match_themselves() {
+    let mut resolution = Resolution::default();
+    let mut skipped = conflict("", "");
+    skipped.member = String::from("docs/*.txt");
+    resolution.add(&skipped, Choice::Skip);
+
+    // Without "-spd" 7z would take the skipped and blocked names as patterns and leave out other files
+    let args = resolution.args("any.7z", "out", &[String::from("docs/a?.txt")], &["docs/[ab].txt"]);
+    let names = args.iter().position(|arg| arg == "--").unwrap();
+    assert!(args[..names].contains(&String::from("-spd")));
+    assert!(args[..names].contains(&String::from("-x!docs/*.txt")));
+    assert!(args[..names].contains(&String::from("-x!docs/[ab].txt")));
+}
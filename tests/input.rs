@@ -0,0 +1,93 @@
+use content_7z::window::input::Input;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn typed(value: &str) -> Input {
+    let mut input = Input::new("Name:");
+    for ch in value.chars() {
+        input.insert(ch);
+    }
+    input
+}
+
+fn press(input: &mut Input, code: KeyCode, modifiers: KeyModifiers) {
+    assert!(input.key(&KeyEvent::new(code, modifiers)));
+}
+
+#[test]
+fn edits_at_the_cursor() {
+    let mut input = typed("hello world");
+    input.home();
+    input.right();
+    input.delete();
+    input.insert('a');
+    assert_eq!(input.value, "hallo world");
+
+    input.end();
+    input.backspace();
+    assert_eq!(input.value, "hallo worl");
+    assert_eq!(input.cursor, input.value.len());
+}
+
+#[test]
+fn never_splits_characters() {
+    let mut input = typed("cafe\u{301}👍🏽日本");
+    input.left();
+    input.left();
+    input.left();
+    assert_eq!(&input.value[input.cursor..], "👍🏽日本");
+    input.backspace();
+    assert_eq!(input.value, "caf👍🏽日本");
+    input.delete();
+    assert_eq!(input.value, "caf日本");
+}
+
+#[test]
+fn jumps_and_deletes_words() {
+    let mut input = typed("extract docs/日本語 now");
+    press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(&input.value[input.cursor..], "now");
+    press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(&input.value[input.cursor..], "日本語 now");
+    press(&mut input, KeyCode::Char('f'), KeyModifiers::ALT);
+    assert_eq!(&input.value[input.cursor..], " now");
+
+    press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    assert_eq!(input.value, "extract docs/ now");
+    press(&mut input, KeyCode::Char('k'), KeyModifiers::CONTROL);
+    assert_eq!(input.value, "extract docs/");
+    press(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+    assert_eq!(input.value, "");
+}
+
+#[test]
+fn paste_is_one_line() {
+    let mut input = typed("ab");
+    input.left();
+    input.insert_str("one\ntwo\x07");
+    assert_eq!(input.value, "aone twob");
+    assert_eq!(&input.value[input.cursor..], "b");
+}
+
+#[test]
+fn history_keeps_the_draft() {
+    let mut input = Input::with_history("Pipe to:", "pipe", vec![String::from("less"), String::from("wc -l")]);
+    input.insert('g');
+
+    press(&mut input, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(input.value, "wc -l");
+    press(&mut input, KeyCode::Up, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(input.value, "less");
+    press(&mut input, KeyCode::Down, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(input.value, "g");
+}
+
+#[test]
+fn view_follows_the_cursor() {
+    let mut input = typed("0123456789");
+    assert_eq!(input.view(20), (String::from("0123456789"), 10));
+    assert_eq!(input.view(6), (String::from("56789"), 5));
+    input.home();
+    assert_eq!(input.view(6), (String::from("012345"), 0));
+}
@@ -0,0 +1,80 @@
+use config::{Config, File, FileFormat};
+use content_7z::window::{keymap::{Action, KeyBinding, Keymap}, text};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+fn keymap(toml: &str) -> Keymap {
+    let config = Config::builder().add_source(File::from_str(toml, FileFormat::Toml)).build().unwrap();
+    Keymap::from(&config)
+}
+
+#[test]
+fn parses_keys_and_modifiers() {
+    assert_eq!(KeyBinding::parse("o"), Some(KeyBinding::new(KeyCode::Char('o'), KeyModifiers::NONE)));
+    assert_eq!(KeyBinding::parse("Ctrl+O"), Some(KeyBinding::new(KeyCode::Char('O'), KeyModifiers::CONTROL)));
+    assert_eq!(KeyBinding::parse("alt-enter"), Some(KeyBinding::new(KeyCode::Enter, KeyModifiers::ALT)));
+    assert_eq!(KeyBinding::parse("shift-left"), Some(KeyBinding::new(KeyCode::Left, KeyModifiers::SHIFT)));
+    assert_eq!(KeyBinding::parse("f12"), Some(KeyBinding::new(KeyCode::F(12), KeyModifiers::NONE)));
+    assert_eq!(KeyBinding::parse("-"), Some(KeyBinding::new(KeyCode::Char('-'), KeyModifiers::NONE)));
+    assert_eq!(KeyBinding::parse("f13"), None);
+    assert_eq!(KeyBinding::parse("nothing"), None);
+}
+
+#[test]
+fn shift_with_letters_is_the_capital() {
+    assert_eq!(KeyBinding::parse("shift-x"), KeyBinding::parse("X"));
+    assert_eq!(KeyBinding::parse("ctrl-shift-x"), Some(KeyBinding::new(KeyCode::Char('X'), KeyModifiers::CONTROL)));
+    assert_eq!(KeyBinding::parse("shift-x").unwrap().name(), "X");
+    // Which symbol shift gives depends on the keyboard
+    assert_eq!(KeyBinding::parse("shift-1"), None);
+}
+
+#[test]
+fn single_letters_are_not_modifiers() {
+    assert_eq!(KeyBinding::parse("s-x"), None);
+    assert_eq!(KeyBinding::parse("c-x"), None);
+    assert_eq!(KeyBinding::parse("a-x"), None);
+}
+
+#[test]
+fn events_match_their_bindings() {
+    let keymap = Keymap::default_preset();
+    assert_eq!(keymap.action(&key(KeyCode::Char('o'), KeyModifiers::NONE)), Some(Action::Open));
+    // Terminals send shift along with capitals
+    assert_eq!(keymap.action(&key(KeyCode::Char('O'), KeyModifiers::SHIFT)), Some(Action::OpenWith));
+    assert_eq!(keymap.action(&key(KeyCode::Char('z'), KeyModifiers::CONTROL)), Some(Action::Suspend));
+    assert_eq!(keymap.action(&key(KeyCode::Char('j'), KeyModifiers::NONE)), None);
+    assert_eq!(Keymap::vim_preset().action(&key(KeyCode::Char('j'), KeyModifiers::NONE)), Some(Action::Down));
+}
+
+#[test]
+fn config_keys_replace_the_preset() {
+    let keymap = keymap("[keys]\npreset = \"vim\"\nopen-with = \"ctrl-o\"\nview = [\"shift-v\", \"s-v\", \"f3\"]\n");
+    assert_eq!(keymap.keys_for(Action::OpenWith), ["ctrl-o"]);
+    assert_eq!(keymap.keys_for(Action::View), ["V", "f3"]);
+    assert_eq!(keymap.action(&key(KeyCode::Char('O'), KeyModifiers::SHIFT)), None);
+    assert_eq!(keymap.action(&key(KeyCode::Char('V'), KeyModifiers::SHIFT)), Some(Action::View));
+    assert_eq!(keymap.action(&key(KeyCode::Char('k'), KeyModifiers::NONE)), Some(Action::Up));
+}
+
+#[test]
+fn help_aligns_wide_keys() {
+    let keymap = keymap("[keys]\nview = \"日\"\n");
+    let lines = keymap.help_lines();
+    let columns: Vec<usize> = Action::ALL.iter()
+        .map(|action| {
+            let line = lines.iter().find(|line| line.ends_with(action.description())).unwrap();
+            text::width(line) - text::width(action.description())
+        })
+        .collect();
+    assert!(columns.iter().all(|column| *column == columns[0]));
+}
+
+#[test]
+fn wide_keys_are_not_cut() {
+    assert_eq!(KeyBinding::parse("日本"), None);
+    assert_eq!(KeyBinding::parse("日").map(|binding| binding.name()), Some(String::from("日")));
+}
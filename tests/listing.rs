@@ -0,0 +1,27 @@
+use content_7z::{files::entry::EntryType, zip_manager::manager::parse_entry};
+
+#[test]
+fn parses_listing_lines() {
+    let (name, entry_type, info) = parse_entry("2024-06-01 12:00:00 ....A         1024          512  docs/日本語 file.txt").unwrap();
+    assert_eq!(name, "docs/日本語 file.txt");
+    assert!(matches!(entry_type, EntryType::File));
+    assert_eq!((info.size, info.compressed), (1024, Some(512)));
+    assert_eq!(info.date, "2024-06-01 12:00");
+
+    let (name, entry_type, info) = parse_entry("2024-06-01 12:00:00 D....            0            0  docs").unwrap();
+    assert_eq!(name, "docs");
+    assert!(matches!(entry_type, EntryType::Folder));
+    assert_eq!(info.size, 0);
+}
+
+#[test]
+fn solid_archives_have_no_compressed_size() {
+    let (_, _, info) = parse_entry("2024-06-01 12:00:00 ....A          300               b.txt").unwrap();
+    assert_eq!((info.size, info.compressed), (300, None));
+}
+
+#[test]
+fn stops_at_the_closing_line() {
+    assert!(parse_entry("------------------- ----- ------------ ------------  ------------------------").is_none());
+    assert!(parse_entry("").is_none());
+}
@@ -0,0 +1,41 @@
+use content_7z::window::opener::{self, Opener};
+use std::path::Path;
+
+// Runs the line the way an opener would, with echo in place of the program
+fn run(command: &str, file: &str) -> String {
+    let line = opener::command_line(command, Path::new(file));
+    let output = opener::shell(&line).output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn fills_every_placeholder() {
+    let file = Path::new("/ws/docs/a.txt");
+    assert_eq!(opener::command_line("less {}", file), "less '/ws/docs/a.txt'");
+    assert_eq!(opener::command_line("cp {path} {dir}/{name}.bak", file), "cp '/ws/docs/a.txt' '/ws/docs'/'a.txt'.bak");
+    assert_eq!(opener::command_line("less", file), "less '/ws/docs/a.txt'");
+    assert_eq!(opener::command_line("awk '{print}' {}", file), "awk '{print}' '/ws/docs/a.txt'");
+}
+
+#[test]
+fn names_with_placeholders_are_not_filled_again() {
+    assert_eq!(run("echo {path}", "/ws/x{};id;#"), "/ws/x{};id;#\n");
+    assert_eq!(run("echo {}", "/ws/x{path}y"), "/ws/x{path}y\n");
+    assert_eq!(run("echo {name} {dir}", "/ws/{dir}/{name}"), "{name} /ws/{dir}\n");
+}
+
+#[test]
+fn names_with_quotes_stay_one_argument() {
+    assert_eq!(run("echo {}", "/ws/it's;id"), "/ws/it's;id\n");
+    assert_eq!(run("printf '%s|' {path} {name}", "/ws/a'b c"), "/ws/a'b c|a'b c|");
+}
+
+#[test]
+fn opener_matches_rules() {
+    let by_extension = Opener::new("PDF", "zathura");
+    assert!(by_extension.matches_name("book.pdf"));
+    assert!(!by_extension.matches_name("pdf"));
+    assert!(Opener::new("*.tar.*", "tar tf").matches_name("a.TAR.gz"));
+    assert!(Opener::new("image/*", "feh").matches("a.bin", "image/png"));
+    assert!(!Opener::new("image/png", "feh").matches("a.png", "image/jpeg"));
+}
@@ -0,0 +1,30 @@
+use content_7z::window::{keymap::Action, palette::{Command, Palette}};
+
+fn typed(text: &str) -> Palette {
+    let mut palette = Palette::new();
+    palette.open();
+    for ch in text.chars() {
+        palette.push(ch);
+    }
+    palette
+}
+
+#[test]
+fn exact_names_come_first() {
+    let palette = typed("view");
+    assert_eq!(palette.matches()[0], Command::Action(Action::View));
+    assert_eq!(palette.chosen(), Some(Command::Action(Action::View)));
+}
+
+#[test]
+fn enter_runs_only_the_highlighted_match() {
+    let mut palette = typed("re");
+    let matches = palette.matches();
+    assert!(matches.len() > 1);
+    assert_eq!(palette.chosen(), Some(matches[0]));
+    palette.select_next(true);
+    assert_eq!(palette.chosen(), Some(matches[1]));
+
+    assert!(typed("zzz").matches().is_empty());
+    assert_eq!(typed("zzz").chosen(), None);
+}
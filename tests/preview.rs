@@ -0,0 +1,73 @@
+use content_7z::{
+    files::{entry::EntryType, folder::Folder},
+    window::preview::{hex_lines, hex_row_size, is_binary, wrap, Preview, PreviewState, PREVIEW_DELAY},
+};
+use std::thread;
+
+#[test]
+fn only_longer_members_are_truncated() {
+    let exact = Preview::text("/a.txt", b"12345".to_vec(), 5);
+    assert!(!exact.truncated);
+    assert_eq!(exact.lines(20), ["12345"]);
+
+    let longer = Preview::text("/a.txt", b"123456".to_vec(), 5);
+    assert!(longer.truncated);
+    assert_eq!(longer.bytes, b"12345");
+    assert_eq!(longer.lines(20), ["12345", "..."]);
+}
+
+#[test]
+fn tells_binary_from_text() {
+    assert!(!is_binary("日本語のテキスト\n\ttabs\r\n".as_bytes()));
+    assert!(is_binary(b"ELF\0\x01\x02"));
+    assert!(is_binary(&[0xff, 0xfe, b'a']));
+    // A character cut at the end of the preview is still text
+    assert!(!is_binary(&"日本".as_bytes()[..4]));
+    assert!(Preview::text("/a.bin", vec![0; 8], 8).binary);
+}
+
+#[test]
+fn wraps_and_dumps_lines() {
+    assert_eq!(wrap("a\tb\x07c", 20), ["a    bc"]);
+    assert_eq!(wrap("abcdef", 4), ["abcd", "ef"]);
+
+    assert_eq!(hex_row_size(80), 16);
+    assert_eq!(hex_row_size(40), 4);
+    assert_eq!(hex_lines(b"AB\n", 16, 80), [format!("00000010  41 42 0a {}AB.", "   ".repeat(13))]);
+}
+
+#[test]
+fn folders_list_their_entries() {
+    let mut root = Folder::new("");
+    root.add_entry("docs/a.txt", &EntryType::File);
+    root.add_entry("b.txt", &EntryType::File);
+    let preview = Preview::folder("/", &root);
+    assert_eq!(preview.lines(20), ["docs/", "b.txt"]);
+}
+
+#[test]
+fn waits_before_loading() {
+    let mut state = PreviewState::new();
+    assert!(!state.want("/a.txt"));
+    thread::sleep(PREVIEW_DELAY);
+    assert!(state.want("/a.txt"));
+
+    state.current = Some(Preview::text("/a.txt", vec![], 10));
+    assert!(!state.want("/a.txt"));
+    assert!(!state.want("/b.txt"));
+}
+
+#[test]
+fn loading_shows_a_placeholder() {
+    assert_eq!(Preview::loading("/a.txt").lines(20), ["Loading…"]);
+    assert_eq!(Preview::loading("/a.txt").lines(4), ["Loa…"]);
+    assert_eq!(Preview::error("/a.txt", String::from("Cannot execute")).lines(20), ["Cannot execute"]);
+
+    let mut state = PreviewState::new();
+    state.show(Preview::loading("/a.txt"));
+    assert!(!state.is_loading());
+    assert!(!state.update());
+    assert!(!state.want("/a.txt"));
+    state.clear();
+    assert!(state.current.is_none());
+}
@@ -0,0 +1,327 @@
+use config::{Config, File, FileFormat};
+use content_7z::{
+    files::{entry::{EntryInfo, EntryType}, folder::Folder},
+    window::{
+        render::{print_header, print_menu, print_palette, print_select, show_dialog},
+        text,
+        screen::Screen,
+        select::Select,
+        window::{SortOrder, Window},
+    },
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io::Write;
+
+fn window(width: u16, height: u16) -> Window {
+    let mut root = Folder::new("");
+    root.add_entry("docs/readme.txt", &EntryType::File);
+    root.add_entry("docs/日本語の説明書.txt", &EntryType::File);
+    root.add_entry("notes.md", &EntryType::File);
+
+    let mut win = Window::new(Config::builder().build().unwrap(), width, height);
+    win.assign_path(String::from("backup.7z"));
+    win.assign_root(root);
+    win
+}
+
+fn draw(win: &Window) -> Vec<String> {
+    print_header(win);
+    print_menu(win);
+    win.screen.lines()
+}
+
+#[test]
+fn browser_snapshot() {
+    let win = window(40, 10);
+    assert_eq!(draw(&win), vec![
+        "┌──────────────────────────────────────┐",
+        "│backup.7z                             │",
+        "└──────────────────────────────────────┘",
+        "┌──────────────────────────────────────┐",
+        "│[+] docs                              │",
+        "│--- notes.md                          │",
+        "│                                      │",
+        "│                                      │",
+        "│                                      │",
+        "└─ 1/2 · 2 items · 0 B ────────────────┘",
+    ]);
+}
+
+#[test]
+fn header_shows_breadcrumbs() {
+    let mut win = window(40, 6);
+    assert!(win.go_to("docs"));
+    let lines = draw(&win);
+    assert_eq!(lines[1], "│backup.7z › docs                      │");
+}
+
+#[test]
+fn wide_names_keep_the_border() {
+    let mut win = window(24, 8);
+    assert!(win.go_to("docs"));
+    let lines = draw(&win);
+    assert!(lines[5].starts_with("│--- 日本語の"));
+    assert!(lines[5].ends_with('│'));
+    // The second column of a wide character has no symbol of its own
+    assert_eq!(win.screen.cell(5, 5).symbol, "日");
+    assert_eq!(win.screen.cell(6, 5).symbol, "");
+    for line in &lines {
+        assert_eq!(content_7z::window::text::width(line), 24);
+    }
+}
+
+// A listing that arrives in two parts, as it does while the archive is loaded
+fn listed_window() -> Window {
+    let entry = |name: &str, size: u64, date: &str| {
+        (String::from(name), EntryType::File, EntryInfo { size, date: String::from(date), ..EntryInfo::default() })
+    };
+    let mut win = Window::new(Config::builder().build().unwrap(), 40, 8);
+    win.assign_path(String::from("backup.7z"));
+    win.assign_root(Folder::new(""));
+    win.add_listed(vec![
+        entry("notes.md", 300, "2024-05-01 10:00"),
+        entry("src/main.rs", 1000, "2023-01-01 10:00"),
+    ]);
+    win.add_listed(vec![
+        entry("src/lib.rs", 1000, "2024-06-01 10:00"),
+        entry("Cargo.toml", 2500, "2022-01-01 10:00"),
+    ]);
+    win
+}
+
+fn names(win: &Window) -> Vec<&str> {
+    win.get_current().content.iter().map(Window::entry_name).collect()
+}
+
+#[test]
+fn folders_add_up_what_is_listed() {
+    let win = listed_window();
+    let src = &win.get_current().content[1];
+    assert_eq!(win.entry_size(src), 2000);
+    assert_eq!(win.entry_date(src), "2024-06-01 10:00");
+}
+
+#[test]
+fn sort_and_filter_show_in_the_status_bar() {
+    let mut win = listed_window();
+    assert_eq!(names(&win), vec!["notes.md", "src", "Cargo.toml"]);
+
+    win.set_sort(SortOrder::Name);
+    assert_eq!(names(&win), vec!["src", "Cargo.toml", "notes.md"]);
+    win.set_sort(SortOrder::Size);
+    assert_eq!(names(&win), vec!["Cargo.toml", "src", "notes.md"]);
+    win.set_sort(SortOrder::Date);
+    assert_eq!(names(&win), vec!["src", "notes.md", "Cargo.toml"]);
+    assert_eq!(draw(&win)[7], "└─ 1/3 · by date · 2 items · 2.0 KB ───┘");
+
+    // Entering and leaving folders keeps both
+    win.set_filter("S");
+    assert_eq!(names(&win), vec!["src", "notes.md"]);
+    win.set_current(Folder::new("src"));
+    assert_eq!(names(&win), vec!["lib.rs", "main.rs"]);
+    assert_eq!(draw(&win)[7], "└─ 1/2 · by date · filter \"S\" · 1000… ─┘");
+    win.back_current();
+    assert_eq!(names(&win), vec!["src", "notes.md"]);
+
+    win.set_filter("");
+    win.set_sort(SortOrder::Archive);
+    assert_eq!(names(&win), vec!["notes.md", "src", "Cargo.toml"]);
+    assert_eq!(draw(&win)[7], "└─ 1/3 · 300 B · 2024-05-01 10:00 ─────┘");
+}
+
+#[test]
+fn huge_folders_scroll_to_the_end() {
+    let mut root = Folder::new("");
+    for index in 0..70_000 {
+        root.add_file(&format!("{:05}.txt", index));
+    }
+    let mut win = Window::new(Config::builder().build().unwrap(), 40, 8);
+    win.assign_root(root);
+
+    win.scroll_by(i32::MAX);
+    win.set_cursor(1, 6);
+    assert_eq!(win.selected_index(), 69_999);
+    win.move_down();
+    assert_eq!(win.selected_index(), 69_999);
+    let lines = draw(&win);
+    assert_eq!(lines[6], "│--- 69999.txt                         │");
+    assert!(lines[7].starts_with("└─ 70000/70000 · "));
+}
+
+#[test]
+fn dialog_snapshot() {
+    let mut win = window(40, 10);
+    draw(&win);
+    show_dialog(&mut win, String::from("Extract docs?"));
+    let lines = win.screen.lines();
+    assert_eq!(lines[4], "│[+] docs    ┌─────────────┐           │");
+    assert_eq!(lines[5], "│--- notes.md│Extract docs?│           │");
+    assert_eq!(lines[6], "│            └─────────────┘           │");
+    assert!(win.on_dialog);
+}
+
+#[test]
+fn long_dialogs_fit_the_window() {
+    let mut win = window(20, 6);
+    draw(&win);
+    let report: Vec<String> = (0..40).map(|line| format!("line {}", line)).collect();
+    show_dialog(&mut win, report.join("\n"));
+    let lines = win.screen.lines();
+    // The first lines that fit are shown, the rest are left out
+    let dialog: Vec<String> = lines.iter().map(|line| line.chars().skip(6).take(9).collect()).collect();
+    assert_eq!(dialog, vec!["┌───────┐", "│line 0 │", "│line 1 │", "│line 2 │", "│line 3 │", "└───────┘"]);
+}
+
+#[test]
+fn dialog_over_wide_names() {
+    let mut win = window(24, 8);
+    assert!(win.go_to("docs"));
+    draw(&win);
+    let mut out = vec![];
+    win.screen.flush_to(&mut out).unwrap();
+
+    show_dialog(&mut win, String::from("Extract it?"));
+    let lines = win.screen.lines();
+    // The dialog border lands on wide characters, what is left of them is blanked
+    assert_eq!(lines[5], "│---  └───────────┘.txt│");
+    for line in &lines {
+        assert_eq!(content_7z::window::text::width(line), 24);
+    }
+
+    // Replaying what reached the terminal leaves every cell where the screen has it
+    win.screen.flush_to(&mut out).unwrap();
+    let terminal = Screen::new(24, 8);
+    (&terminal).write_all(&out).unwrap();
+    assert_eq!(terminal.lines(), lines);
+}
+
+#[test]
+fn screen_blanks_both_halves_of_wide_characters() {
+    let screen = Screen::new(6, 2);
+    let mut out = &screen;
+    out.write_all("日本語\x1b[1;2Ha\x1b[1;5Hb".as_bytes()).unwrap();
+    assert_eq!(screen.line(0), " a本b ");
+    assert_eq!(screen.cell(5, 0).symbol, " ");
+
+    // Erasing from the middle of a wide character, and below the last row, is safe
+    out.write_all("\x1b[2;1H日本\x1b[2;2H\x1b[K\r\n\n\n\x1b[K".as_bytes()).unwrap();
+    assert_eq!(screen.line(1), "      ");
+}
+
+#[test]
+fn flush_sends_only_differences() {
+    let mut win = window(40, 10);
+    draw(&win);
+
+    let mut first = vec![];
+    win.screen.flush_to(&mut first).unwrap();
+    let first = String::from_utf8(first).unwrap();
+    assert!(first.contains("backup.7z"));
+    assert!(first.contains("notes.md"));
+    // The whole update is shown at once
+    assert!(first.starts_with("\x1b[?2026h"));
+    assert!(first.ends_with("\x1b[?2026l"));
+
+    // Drawing the same thing again changes nothing
+    draw(&win);
+    let mut second = vec![];
+    win.screen.flush_to(&mut second).unwrap();
+    assert!(second.is_empty());
+
+    assert!(win.go_to("docs"));
+    draw(&win);
+    let mut third = vec![];
+    win.screen.flush_to(&mut third).unwrap();
+    let third = String::from_utf8(third).unwrap();
+    assert!(third.contains("docs"));
+    assert!(!third.contains("backup.7z"));
+    assert!(!third.contains('┌'));
+
+    // After someone else used the terminal everything is sent again
+    win.screen.invalidate();
+    let mut fourth = vec![];
+    win.screen.flush_to(&mut fourth).unwrap();
+    assert!(String::from_utf8(fourth).unwrap().contains("backup.7z"));
+}
+
+#[test]
+fn screen_understands_styles() {
+    let screen = Screen::new(10, 2);
+    let mut out = &screen;
+    out.write_all(b"\x1b[2;3H\x1b[1;38;2;255;0;0mab\x1b[0mc").unwrap();
+
+    assert_eq!(screen.line(1), "  abc     ");
+    assert_eq!(screen.cursor(), (5, 1));
+    let bold = screen.cell(2, 1).style;
+    assert_eq!(bold.attributes, vec![1]);
+    assert_eq!(bold.fg.as_deref(), Some("38;2;255;0;0"));
+    assert_eq!(screen.cell(4, 1).style, Default::default());
+}
+
+#[test]
+fn select_snapshot() {
+    let mut win = window(40, 16);
+    draw(&win);
+    let themes = ["catppuccin", "tokio dark", "rose pine", "github default"];
+    win.select = Some(Select::filtered("Theme:", themes.iter().map(|theme| theme.to_string()).collect()));
+    for ch in "o".chars() {
+        win.select.as_mut().unwrap().key(&KeyEvent::from(KeyCode::Char(ch)), 4);
+    }
+    win.select.as_mut().unwrap().key(&KeyEvent::from(KeyCode::Down), 4);
+    print_select(&mut win);
+
+    let lines = win.screen.lines();
+    let top = lines.iter().position(|line| line.contains("Theme:")).unwrap();
+    assert!(lines[top + 1].contains("│> o  "));
+    assert!(lines[top + 2].contains("│ tokio dark  "));
+    assert!(lines[top + 3].contains("│ rose pine   "));
+    assert!(lines[top + 4].contains("├"));
+
+    // Only the chosen row is highlighted
+    let border = lines[top + 3].find("│ rose").unwrap();
+    let x = lines[top + 3][..border].chars().count() as u16 + 1;
+    assert!(win.screen.cell(x, top as u16 + 3).style.attributes.contains(&7));
+    assert!(!win.screen.cell(x, top as u16 + 2).style.attributes.contains(&7));
+    assert_eq!(win.select.as_ref().unwrap().chosen(), Some(2));
+}
+
+#[test]
+fn select_in_a_tiny_window() {
+    let mut win = window(30, 4);
+    draw(&win);
+    let themes = ["catppuccin", "tokio dark"];
+    win.select = Some(Select::filtered("Theme:", themes.iter().map(|theme| theme.to_string()).collect()));
+    print_select(&mut win);
+    for line in win.screen.lines() {
+        assert_eq!(content_7z::window::text::width(&line), 30);
+    }
+}
+
+#[test]
+fn palette_aligns_wide_keys() {
+    let config = Config::builder().add_source(File::from_str("[keys]\nview = [\"日\", \"本\"]\n", FileFormat::Toml)).build().unwrap();
+    let mut win = Window::new(config, 120, 30);
+    win.assign_path(String::from("backup.7z"));
+    win.assign_root(Folder::new(""));
+    draw(&win);
+    win.palette.open();
+    for ch in "vi".chars() {
+        win.palette.push(ch);
+    }
+    print_palette(&mut win);
+
+    let lines = win.screen.lines();
+    let column = |description: &str| {
+        let line = lines.iter().find(|line| line.contains(description)).unwrap();
+        text::width(&line[..line.find(description).unwrap()])
+    };
+    let matches = win.palette.matches();
+    assert!(matches.len() > 1);
+    assert!(matches.iter().any(|command| command.name() == "view"));
+    for command in &matches {
+        assert_eq!(column(command.description()), column(matches[0].description()));
+    }
+    for line in &lines {
+        assert_eq!(text::width(line), 120);
+    }
+}
@@ -0,0 +1,56 @@
+use content_7z::{
+    files::entry::EntryInfo,
+    zip_manager::safety::{hazard, path_hazard, safe_join, settle_links, Hazard, LinkPolicy},
+};
+use std::{collections::HashMap, env, fs, os::unix::fs::symlink, path::Path};
+
+#[test]
+fn finds_members_that_leave_the_destination() {
+    assert_eq!(path_hazard("/etc/passwd"), Some(Hazard::Absolute));
+    assert_eq!(path_hazard("C:\\boot.ini"), Some(Hazard::Absolute));
+    assert_eq!(path_hazard("../evil.txt"), Some(Hazard::Escapes));
+    assert_eq!(path_hazard("docs/../../evil.txt"), Some(Hazard::Escapes));
+    assert_eq!(path_hazard("docs/../a.txt"), None);
+    assert_eq!(path_hazard("docs/./a..txt"), None);
+
+    let mut info = HashMap::new();
+    info.insert(String::from("link"), EntryInfo::default());
+    info.insert(String::from("docs"), EntryInfo { folder: true, ..EntryInfo::default() });
+    assert_eq!(hazard(&info, "link/file"), Some(Hazard::ThroughLink));
+    assert_eq!(hazard(&info, "docs/a.txt"), None);
+    assert_eq!(hazard(&info, "link"), None);
+}
+
+#[test]
+fn joins_only_inside_the_root() {
+    let root = Path::new("/out");
+    assert_eq!(safe_join(root, "docs/../a.txt").unwrap(), Path::new("/out/a.txt"));
+    assert_eq!(safe_join(root, "./docs//b.txt").unwrap(), Path::new("/out/docs/b.txt"));
+    assert!(safe_join(root, "../a.txt").is_none());
+    assert!(safe_join(root, "/a.txt").is_none());
+}
+
+#[test]
+fn settles_links_by_policy() {
+    let dir = env::temp_dir().join(format!("content-7z-safety-{}", std::process::id()));
+    let root = dir.join("out");
+    let make = || {
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.txt"), "").unwrap();
+        for (name, target) in [("inside", "a.txt"), ("outside", "../../etc"), ("dangling", "../missing")] {
+            let _ = fs::remove_file(root.join(name));
+            symlink(target, root.join(name)).unwrap();
+        }
+        ["a.txt", "inside", "outside", "dangling"].map(|name| root.join(name))
+    };
+
+    assert_eq!(settle_links(&root, &make(), LinkPolicy::Preserve), 0);
+    assert_eq!(settle_links(&root, &make(), LinkPolicy::FollowWithinRoot), 2);
+    assert!(root.join("inside").exists());
+    assert!(fs::symlink_metadata(root.join("outside")).is_err());
+    assert_eq!(settle_links(&root, &make(), LinkPolicy::Skip), 3);
+    assert!(root.join("a.txt").exists());
+    assert_eq!(LinkPolicy::from_name("follow-within-root"), Some(LinkPolicy::FollowWithinRoot));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
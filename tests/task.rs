@@ -0,0 +1,104 @@
+use content_7z::zip_manager::task::{parse_progress, Progress, Task};
+use std::{
+    fs,
+    sync::{atomic::Ordering, mpsc},
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn parses_extraction_progress() {
+    let progress = parse_progress(" 45% 12 - docs/日本語 file.txt").unwrap();
+    assert_eq!(progress.percent, 45);
+    assert_eq!(progress.files, Some(12));
+    assert_eq!(progress.current, "docs/日本語 file.txt");
+}
+
+#[test]
+fn parses_partial_lines() {
+    let progress = parse_progress("  0%").unwrap();
+    assert_eq!(progress.percent, 0);
+    assert_eq!(progress.files, None);
+    assert_eq!(progress.current, "");
+
+    let progress = parse_progress("100% 3").unwrap();
+    assert_eq!((progress.percent, progress.files), (100, Some(3)));
+    assert_eq!(progress.current, "");
+
+    let progress = parse_progress(" 7% T archive.tar").unwrap();
+    assert_eq!((progress.percent, progress.files), (7, None));
+    assert_eq!(progress.current, "archive.tar");
+}
+
+#[test]
+fn ignores_other_output() {
+    assert!(parse_progress("").is_none());
+    assert!(parse_progress("        ").is_none());
+    assert!(parse_progress("Everything is Ok").is_none());
+    assert!(parse_progress("ratio: 45%").is_none());
+}
+
+#[test]
+fn threads_run_as_tasks() {
+    let mut task = Task::spawn("Copying", |progress, _| {
+        progress.send(Progress::bytes(50, 200, "a.txt")).unwrap();
+        Ok(())
+    });
+    let result = loop {
+        if let Some(result) = task.update() {
+            break result;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    assert_eq!(result, Ok(()));
+    assert_eq!((task.progress.percent, task.progress.current.as_str()), (25, "a.txt"));
+
+    let mut failing = Task::spawn("Copying", |_, _| Err(String::from("No space left")));
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(failing.update(), Some(Err(String::from("No space left"))));
+}
+
+#[test]
+fn cancelled_threads_are_told_to_stop() {
+    let (sender, receiver) = mpsc::channel();
+    let mut task = Task::spawn("Copying", move |_, cancelled| {
+        while !cancelled.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(5));
+        }
+        sender.send(()).unwrap();
+        Ok(())
+    });
+    assert!(task.update().is_none());
+    task.cancel();
+    assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn cancelling_removes_the_folders_it_made() {
+    let root = std::env::temp_dir().join(format!("content-7z-made-{}", std::process::id()));
+    fs::create_dir_all(root.join("kept")).unwrap();
+    fs::write(root.join("kept/old.txt"), "old").unwrap();
+    let file = root.join("out/docs/a.txt");
+    let shared = root.join("kept/new/b.txt");
+
+    let (sender, receiver) = mpsc::channel();
+    let mut task = Task::spawn("Extracting", move |_, cancelled| {
+        while !cancelled.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(5));
+        }
+        sender.send(()).unwrap();
+        Ok(())
+    });
+    task.set_partial(vec![file.clone(), shared.clone()]);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, "new").unwrap();
+    fs::create_dir_all(shared.parent().unwrap()).unwrap();
+    fs::write(root.join("kept/new/other.txt"), "someone else").unwrap();
+    task.cancel();
+    assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+
+    assert!(!root.join("out").exists());
+    assert!(root.join("kept/old.txt").exists());
+    assert!(root.join("kept/new/other.txt").exists());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,76 @@
+use content_7z::window::{
+    breadcrumbs,
+    text::{self, ELLIPSIS},
+};
+
+#[test]
+fn width_counts_columns() {
+    assert_eq!(text::width("file.txt"), 8);
+    assert_eq!(text::width("café.txt"), 8);
+    assert_eq!(text::width("日本語"), 6);
+    assert_eq!(text::width("👍"), 2);
+    // A combining accent is part of the previous character
+    assert_eq!(text::width("cafe\u{301}"), 4);
+}
+
+#[test]
+fn truncate_never_splits_characters() {
+    assert_eq!(text::truncate("日本語フォルダ", 7), String::from("日本語") + ELLIPSIS);
+    assert_eq!(text::truncate("日本語フォルダ", 8), String::from("日本語") + ELLIPSIS);
+    assert_eq!(text::truncate("日本語", 6), "日本語");
+    assert_eq!(text::truncate("cafe\u{301}s", 4), "caf…");
+    assert_eq!(text::truncate("👍👍👍", 4), "👍…");
+    assert_eq!(text::truncate("日本語", 0), "");
+
+    for max in 0..16 {
+        assert!(text::width(&text::truncate("文書ファイル名.txt", max)) <= max);
+    }
+}
+
+#[test]
+fn truncate_left_keeps_the_end() {
+    assert_eq!(text::truncate_left("とても長い名前.txt", 9), String::from(ELLIPSIS) + "名前.txt");
+    assert_eq!(text::truncate_left("とても長い名前.txt", 8), String::from(ELLIPSIS) + "前.txt");
+    assert_eq!(text::truncate_left("short", 10), "short");
+}
+
+#[test]
+fn wrap_fits_every_row() {
+    let rows = text::wrap("日本語のファイル名がとても長い", 7);
+    assert_eq!(rows.concat(), "日本語のファイル名がとても長い");
+    for row in &rows {
+        assert!(text::width(row) <= 7);
+    }
+    assert_eq!(text::wrap("", 5), vec![String::new()]);
+}
+
+#[test]
+fn take_and_skip_follow_columns() {
+    assert_eq!(text::take("ab日本", 3), "ab");
+    assert_eq!(text::take_last("ab日本", 3), "本");
+    assert_eq!(text::skip("日本語", 2), "本語");
+    // Half of a wide character can not be shown
+    assert_eq!(text::skip("日本語", 3), "語");
+    assert_eq!(text::pad("日本", 6), "日本  ");
+}
+
+#[test]
+fn breadcrumbs_collapse_wide_names() {
+    let names: Vec<String> = ["アーカイブ.7z", "第一", "第二", "第三"].iter().map(|name| name.to_string()).collect();
+
+    let crumbs = breadcrumbs::layout(&names, 80);
+    assert_eq!(crumbs.len(), 4);
+    assert_eq!(crumbs[1].column, text::width("アーカイブ.7z › "));
+
+    let crumbs = breadcrumbs::layout(&names, 24);
+    let texts: Vec<&str> = crumbs.iter().map(|crumb| crumb.text.as_str()).collect();
+    assert_eq!(texts, vec!["アーカイブ.7z", ELLIPSIS, "第三"]);
+    assert_eq!(crumbs[1].depth, None);
+    assert_eq!(breadcrumbs::depth_at(&crumbs, crumbs[2].column + 1), Some(4));
+
+    for width in 1..30 {
+        let crumbs = breadcrumbs::layout(&names, width);
+        let last = crumbs.last().unwrap();
+        assert!(last.column + last.width() <= width);
+    }
+}
@@ -0,0 +1,45 @@
+use content_7z::window::viewer::{Viewer, ViewerMode};
+
+fn numbered(count: usize) -> Vec<u8> {
+    (0..count).map(|line| format!("line {}\n", line)).collect::<String>().into_bytes()
+}
+
+#[test]
+fn search_goes_both_ways() {
+    let mut viewer = Viewer::memory("numbers", numbered(100), 40, 12);
+    viewer.search = String::from("line 4");
+    viewer.find(true, true);
+    assert_eq!(viewer.scroll, 4);
+    viewer.find(true, false);
+    assert_eq!(viewer.scroll, 40);
+    viewer.find(false, false);
+    assert_eq!(viewer.scroll, 4);
+
+    viewer.search = String::from("line 100");
+    viewer.find(true, false);
+    assert_eq!(viewer.scroll, 4);
+    assert_eq!(viewer.message.as_deref(), Some("Pattern not found: line 100"));
+    assert!(viewer.pending.is_none());
+}
+
+#[test]
+fn search_in_hex_finds_the_row() {
+    let mut viewer = Viewer::memory("numbers", numbered(100), 80, 12);
+    viewer.toggle_mode();
+    assert!(viewer.mode == ViewerMode::Hex);
+    viewer.search = String::from("line 50");
+    viewer.find(true, true);
+    let offset = numbered(50).len();
+    assert_eq!(viewer.scroll, offset / viewer.row_size());
+}
+
+#[test]
+fn bottom_shows_the_last_page() {
+    let mut viewer = Viewer::memory("numbers", numbered(100), 40, 12);
+    viewer.scroll_bottom();
+    assert!(viewer.pending.is_none());
+    assert_eq!(viewer.scroll, 100 - viewer.page_size());
+    assert_eq!(viewer.position(), Some(100));
+    assert!(!viewer.is_cut());
+    assert!(!viewer.stop());
+}
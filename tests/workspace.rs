@@ -0,0 +1,33 @@
+use content_7z::files::workspace::Workspace;
+use std::{fs, os::unix::fs::PermissionsExt};
+
+#[test]
+fn creates_private_folders_per_archive() {
+    let mut workspace = Workspace::new(false);
+    assert!(workspace.root().is_none());
+
+    let first = workspace.archive_dir("/some/where/a.7z").unwrap();
+    let again = workspace.archive_dir("/some/where/a.7z").unwrap();
+    let other = workspace.archive_dir("/else/where/a.7z").unwrap();
+    let root = workspace.root().unwrap().to_path_buf();
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+    assert!(first.starts_with(&root));
+    assert!(first.file_name().unwrap().to_string_lossy().starts_with("a.7z."));
+    assert_eq!(fs::metadata(&root).unwrap().permissions().mode() & 0o777, 0o700);
+    assert_eq!(fs::metadata(&first).unwrap().permissions().mode() & 0o777, 0o700);
+
+    fs::write(first.join("file"), "").unwrap();
+    workspace.remove().unwrap();
+    assert!(!root.exists());
+    assert!(workspace.root().is_none());
+}
+
+#[test]
+fn kept_workspaces_stay_on_disk() {
+    let mut workspace = Workspace::new(true);
+    let root = workspace.create().unwrap().to_path_buf();
+    drop(workspace);
+    assert!(root.exists());
+    fs::remove_dir_all(root).unwrap();
+}